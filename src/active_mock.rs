@@ -1,19 +1,48 @@
-use crate::{Match, Mock, Request};
-use http_types::Response;
-use std::time::Duration;
+use crate::mock::MockSummary;
+use crate::{Match, Mock, Request, ResponseTemplate};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
 
 // Given the behaviour specification as a `Mock`, keep track of runtime information concerning
 // this mock - e.g. how many times it matched on a incoming request.
 pub(crate) struct ActiveMock {
+    // Unique identifier assigned by the `MockActor` when the mock is registered.
+    id: u64,
     specification: Mock,
     n_matched_requests: u64,
+    // How many incoming requests satisfied all the matchers, regardless of them getting a
+    // response from this mock (see `only_on_call`).
+    n_matching_attempts: u64,
+    // Requests this mock matched on, in the order they were received - only kept if
+    // `record_requests` is set.
+    received_requests: Vec<Request>,
+    record_requests: bool,
 }
 
 impl ActiveMock {
-    pub(crate) fn new(specification: Mock) -> Self {
+    /// If `record_requests` is `true`, the requests matched by the mock are kept around, to be
+    /// retrieved via `received_requests`.
+    pub(crate) fn new(id: u64, specification: Mock, record_requests: bool) -> Self {
         Self {
+            id,
             specification,
             n_matched_requests: 0,
+            n_matching_attempts: 0,
+            received_requests: vec![],
+            record_requests,
+        }
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// A label identifying this mock in diagnostics: the name assigned via `Mock::named`,
+    /// falling back to its identifier for unnamed mocks.
+    pub(crate) fn label(&self) -> String {
+        match &self.specification.name {
+            Some(name) => name.to_owned(),
+            None => format!("unnamed mock #{}", self.id),
         }
     }
 
@@ -26,22 +55,60 @@ impl ActiveMock {
         if Some(self.n_matched_requests) == self.specification.max_n_matches {
             // Skip the actual check if we are already at our maximum of matched requests.
             false
+        } else if !self.is_active() {
+            // Outside of its window (or while disabled) the mock does not even look at the request.
+            false
         } else {
-            let matched = self
+            let mut matched = self
                 .specification
                 .matchers
                 .iter()
                 .all(|matcher| matcher.matches(request));
 
+            if matched {
+                self.n_matching_attempts += 1;
+                if let Some(n) = self.specification.only_on_call {
+                    matched = self.n_matching_attempts == n;
+                }
+            }
+
             if matched {
                 // Increase match count
                 self.n_matched_requests += 1;
+                if self.record_requests {
+                    let mut matched_request = request.clone();
+                    matched_request.matched_mock = Some(self.label());
+                    self.received_requests.push(matched_request);
+                }
+                if let Some(sink) = &self.specification.body_sink {
+                    // A panic while holding the lock elsewhere must not take down the server.
+                    let mut sink = sink.lock().unwrap_or_else(|e| e.into_inner());
+                    sink.extend_from_slice(&request.body);
+                }
+                if let Some(callback) = &self.specification.on_match {
+                    (callback.0)(request);
+                }
             }
 
             matched
         }
     }
 
+    /// Whether the mock is enabled (see `MockGuard::disable`) and the current time falls
+    /// within the window set via `Mock::active_between`, if any.
+    fn is_active(&self) -> bool {
+        if !self.specification.enabled.load(Ordering::SeqCst) {
+            return false;
+        }
+        match self.specification.active_window {
+            Some((start, end)) => {
+                let now = Instant::now();
+                start <= now && now < end
+            }
+            None => true,
+        }
+    }
+
     /// Verify if this mock has verified the expectations set at creation time
     /// over the number of invocations.
     /// Returns true if expectations have been satisfied, false otherwise.
@@ -51,11 +118,34 @@ impl ActiveMock {
             .contains(self.n_matched_requests)
     }
 
-    pub(crate) fn response(&self) -> Response {
-        self.specification.response()
+    /// Take a snapshot of the state of this mock.
+    pub(crate) fn summary(&self) -> MockSummary {
+        MockSummary {
+            name: self.specification.name.clone(),
+            matchers: self
+                .specification
+                .matchers
+                .iter()
+                .map(|matcher| matcher.name())
+                .collect(),
+            expectation: self.specification.expectation.to_string(),
+            expectation_satisfied: self.verify(),
+            n_matched_requests: self.n_matched_requests,
+        }
+    }
+
+    /// Forget about all the requests received so far, as if the mock had just been registered.
+    pub(crate) fn reset_counters(&mut self) {
+        self.n_matched_requests = 0;
+        self.n_matching_attempts = 0;
+        self.received_requests.clear();
+    }
+
+    pub(crate) fn received_requests(&self) -> &[Request] {
+        &self.received_requests
     }
 
-    pub(crate) fn delay(&self) -> &Option<Duration> {
-        &self.specification.delay()
+    pub(crate) fn response_template(&self, request: &Request) -> ResponseTemplate {
+        self.specification.response_template(request)
     }
 }
@@ -0,0 +1,592 @@
+//! A minimal server-side implementation of HTTP/1.1.
+//!
+//! We used to rely on `async_h1::accept` to turn a TCP stream into a sequence of
+//! request/response pairs.
+//! `async-h1` does not give us control over what actually gets written on the wire, which
+//! we need to simulate protocol-level behaviours (e.g. sending trailers after the body).
+//!
+//! Requests are read in full (head and body) and turned into our own `Request` type before
+//! being handed over to the endpoint: matchers need the whole body anyway, and we get to
+//! preserve details (e.g. extension methods) that `http_types::Request` cannot represent.
+use crate::instrumentation::RequestSpan;
+use crate::Request;
+use async_std::future::timeout;
+use async_std::io::{self, BufRead, BufReader, Write};
+use async_std::net::TcpStream;
+use async_std::prelude::*;
+use futures_timer::Delay;
+use http_types::headers::{HeaderName, HeaderValue, CONTENT_LENGTH, TRANSFER_ENCODING};
+use http_types::{ensure, ensure_eq, format_err};
+use http_types::{Method, Response, StatusCode, Trailers, Url};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::net::Shutdown;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Maximum number of headers we accept in a request head.
+const MAX_HEADERS: usize = 128;
+/// Maximum length, in bytes, of a request head - it prevents CWE-400 DDOS with large
+/// HTTP headers.
+const MAX_HEAD_LENGTH: usize = 8 * 1024;
+/// Maximum length, in bytes, of a request body - whatever the client declares, we never
+/// buffer more than this.
+const MAX_BODY_LENGTH: usize = 64 * 1024 * 1024;
+/// How long we wait for the next request before closing the connection, unless
+/// overridden via `MockServerBuilder::idle_timeout`.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long we keep reading (and discarding) the leftovers of a request we could not parse
+/// before closing the connection.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+/// The number returned by `httparse` when the request is HTTP/1.1.
+const HTTP_1_1_VERSION: u8 = 1;
+/// The number returned by `httparse` when the request is HTTP/1.0.
+const HTTP_1_0_VERSION: u8 = 0;
+
+/// Directive, set in the local state of a `Response`, to send a `Content-Length` header with
+/// the specified value instead of the actual length of the body.
+///
+/// It produces invalid HTTP on purpose: the connection is closed after the response has been
+/// sent.
+pub(crate) struct DeclaredContentLength(pub(crate) usize);
+
+/// Directive, set in the local state of a `Response`, listing the headers that must be sent
+/// first, in the specified order.
+///
+/// Headers that are not listed follow, sorted by name: the head of a response is always the
+/// same, byte for byte.
+pub(crate) struct HeaderOrder(pub(crate) Vec<HeaderName>);
+
+/// Directive, set in the local state of a `Response`, to write the body (and its framing)
+/// at the specified rate, in bytes per second.
+///
+/// The head is written straight away.
+pub(crate) struct Throughput(pub(crate) u64);
+
+/// Knobs tuning the behaviour of every connection accepted by a `MockServer`.
+#[derive(Clone, Debug)]
+pub(crate) struct ConnectionSettings {
+    /// How long an idle connection (i.e. just accepted or right after a response has been
+    /// sent) is kept open waiting for the client to send the next request in full.
+    pub(crate) idle_timeout: Duration,
+    /// Respond as soon as the head of a request has been read, discarding its body.
+    pub(crate) respond_before_body: bool,
+    /// Accept HTTP/1.0 requests and speak HTTP/1.0: one response per connection, never chunked.
+    pub(crate) http_1_0: bool,
+    /// Close the connection after serving this many requests on it, if set.
+    pub(crate) max_requests: Option<usize>,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            respond_before_body: false,
+            http_1_0: false,
+            max_requests: None,
+        }
+    }
+}
+
+/// Take a TCP stream and convert it into sequential HTTP request/response pairs, calling
+/// `endpoint` to get the response for each request.
+///
+/// A `RequestSpan` is opened as soon as the head of a request has been read: it covers
+/// reading the body, `endpoint` (which gets a handle to it) and writing the response.
+///
+/// The connection is kept alive until the client closes it or fails to send the next
+/// request within `settings.idle_timeout`.  
+/// Requests we cannot parse (e.g. a garbled request line or a head larger than 8kb) get a
+/// `400 Bad Request` and the connection is closed.
+///
+/// If `settings.respond_before_body` is set, `endpoint` is called as soon as the head of
+/// the request has been read: the response is written while we discard the body, then
+/// the connection is closed.  
+/// If `settings.http_1_0` is set, HTTP/1.0 requests are accepted alongside HTTP/1.1 ones and
+/// every response is sent as HTTP/1.0, closing the connection right after.  
+/// If `settings.max_requests` is set, the connection is closed (with a `Connection: close`
+/// header on the last response) once that many requests have been served on it.
+pub(crate) async fn accept<F, Fut>(
+    addr: &str,
+    stream: TcpStream,
+    settings: &ConnectionSettings,
+    endpoint: F,
+) -> http_types::Result<()>
+where
+    F: Fn(Request, RequestSpan) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    // The same buffered reader is used for the whole lifetime of the connection: a client
+    // pipelining requests might have sent more than one request in the same packet.
+    let mut reader = BufReader::new(stream.clone());
+    let mut writer = stream;
+    let mut n_served: usize = 0;
+    loop {
+        let next_request = async {
+            let (mut request, framing) = match decode(addr, &mut reader, settings.http_1_0).await? {
+                Some(decoded) => decoded,
+                None => return Ok(None),
+            };
+            let span = RequestSpan::new(&request);
+            if !(settings.respond_before_body && framing.has_body()) {
+                // HTTP/1.0 clients do not know about interim responses.
+                let send_continue = !settings.http_1_0;
+                let reading = read_body(
+                    &mut reader,
+                    &mut writer,
+                    &mut request,
+                    &framing,
+                    send_continue,
+                );
+                span.receiving(reading).await?;
+            }
+            http_types::Result::Ok(Some((request, framing, span)))
+        };
+        let (request, framing, span) = match timeout(settings.idle_timeout, next_request).await {
+            Ok(Ok(Some(decoded))) => decoded,
+            // EOF or timeout
+            Ok(Ok(None)) | Err(_) => break,
+            // The connection broke down, there is no one to respond to.
+            Ok(Err(e)) if e.downcast_ref::<io::Error>().is_some() => return Err(e),
+            // We could not make sense of the request: we tell the client before hanging up,
+            // we cannot know where the next request would start.
+            Ok(Err(e)) => {
+                let mut response = Response::new(StatusCode::BadRequest);
+                response.insert_header("connection", "close").unwrap();
+                respond(&mut writer, response, false, settings.http_1_0).await?;
+                // Closing a socket with unread data makes the operating system reset the
+                // connection, possibly before the client got to read our response: we stop
+                // writing and discard what the client is still sending for a little while.
+                writer.shutdown(Shutdown::Write)?;
+                let _ = timeout(DRAIN_TIMEOUT, io::copy(&mut reader, &mut io::sink())).await;
+                return Err(e);
+            }
+        };
+
+        // Responses to `HEAD` requests carry the headers a `GET` would get, but no body.
+        let omit_body = request.method == Some(Method::Head);
+        let mut response = endpoint(request, span.clone()).await;
+        span.record_status(response.status());
+        n_served += 1;
+        let limit_reached = settings.max_requests.map_or(false, |max| n_served >= max);
+
+        if settings.respond_before_body && framing.has_body() {
+            // The client might still be busy sending the body, without reading anything until
+            // it's done: we keep consuming the body while writing the response, so that
+            // neither side is left blocked on a full socket buffer.
+            response.insert_header("connection", "close").unwrap();
+            let skip = timeout(settings.idle_timeout, skip_body(&mut reader, &framing));
+            let responding =
+                span.writing(respond(&mut writer, response, omit_body, settings.http_1_0));
+            let (responded, _) = futures::join!(responding, skip);
+            responded?;
+            break;
+        }
+
+        // After a response with broken framing the client cannot make sense of the stream
+        // anymore: we close the connection.
+        // HTTP/1.0 has no persistent connections: the end of the connection marks the end of
+        // the exchange.
+        // The same goes for connections that served as many requests as they were allowed to.
+        let close = settings.http_1_0
+            || limit_reached
+            || response.local().contains::<DeclaredContentLength>();
+        if settings.http_1_0 || limit_reached {
+            response.insert_header("connection", "close").unwrap();
+        }
+        span.writing(respond(&mut writer, response, omit_body, settings.http_1_0))
+            .await?;
+        if close {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// How the body of a request is delimited on the wire.
+enum Framing {
+    /// `Content-Length` bytes - 0 if the header is missing.
+    Length(usize),
+    /// `Transfer-Encoding: chunked`.
+    Chunked,
+}
+
+impl Framing {
+    fn has_body(&self) -> bool {
+        match self {
+            Framing::Length(length) => *length > 0,
+            Framing::Chunked => true,
+        }
+    }
+}
+
+/// Read the head of the next request from `reader`.
+///
+/// The body of the returned `Request` is empty: it is up to the caller to read it (or to
+/// skip it) according to the returned `Framing`.  
+/// It returns `None` if the client closed the connection before sending a new request.  
+/// HTTP/1.0 requests are rejected unless `accept_http_1_0` is `true`.
+async fn decode<R>(
+    addr: &str,
+    reader: &mut R,
+    accept_http_1_0: bool,
+) -> http_types::Result<Option<(Request, Framing)>>
+where
+    R: BufRead + Unpin,
+{
+    let mut head = Vec::new();
+    // Keep reading lines until we hit the empty line marking the end of the head.
+    loop {
+        // We never buffer more than `MAX_HEAD_LENGTH` bytes, even if the client never sends
+        // a line break.
+        let limit = (MAX_HEAD_LENGTH - head.len()) as u64;
+        let bytes_read = (&mut *reader)
+            .take(limit)
+            .read_until(b'\n', &mut head)
+            .await?;
+        if bytes_read == 0 {
+            return Ok(None);
+        }
+        ensure!(
+            head.len() < MAX_HEAD_LENGTH,
+            "Head byte length should be less than 8kb"
+        );
+        if head.ends_with(b"\r\n\r\n") {
+            break;
+        }
+        // Bail out as soon as what we got so far cannot be the beginning of a valid head
+        // (e.g. a garbage request line), instead of waiting for more.
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        httparse::Request::new(&mut headers).parse(&head)?;
+    }
+
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut parsed = httparse::Request::new(&mut headers);
+    let status = parsed.parse(&head)?;
+    ensure!(!status.is_partial(), "Malformed HTTP head");
+
+    let raw_method = parsed
+        .method
+        .ok_or_else(|| format_err!("No method found"))?;
+    let raw_target = parsed.path.ok_or_else(|| format_err!("No uri found"))?;
+    let version = parsed
+        .version
+        .ok_or_else(|| format_err!("No version found"))?;
+    if !(accept_http_1_0 && version == HTTP_1_0_VERSION) {
+        ensure_eq!(version, HTTP_1_1_VERSION, "Unsupported HTTP version");
+    }
+
+    // `http_types::Method` has no variant for extension methods: they are left unparsed,
+    // matchers can still get the actual method from `raw_method`.
+    let method = Method::from_str(raw_method).ok();
+    let url = if raw_target.starts_with('/') {
+        // Origin form, the most common one
+        Url::parse(&format!("{}{}", addr, raw_target))?
+    } else if raw_target == "*" {
+        // Asterisk form, used by server-wide `OPTIONS` requests
+        Url::parse(addr)?
+    } else {
+        // Absolute form, used when talking to a proxy
+        Url::parse(raw_target)?
+    };
+
+    let mut headers: HashMap<HeaderName, Vec<HeaderValue>> = HashMap::new();
+    for header in parsed.headers.iter() {
+        let name = HeaderName::from_str(header.name)?;
+        let value = HeaderValue::from_str(std::str::from_utf8(header.value)?)?;
+        // Repeated headers are preserved, in order: matchers might care about them
+        // (e.g. to detect request smuggling attempts).
+        headers.entry(name).or_default().push(value);
+    }
+
+    // If both `Transfer-Encoding` and `Content-Length` are specified, the former takes
+    // precedence (RFC 7230, section 3.3.3).
+    // We keep the `Content-Length` header around: matchers can check what the client declared.
+    // Codings are listed in the order they were applied: `chunked`, if present, must be the
+    // last one. Otherwise the length of the body cannot be determined and the request is
+    // rejected.
+    let final_coding = headers
+        .get(&TRANSFER_ENCODING)
+        .and_then(|v| v.last())
+        .map(|encoding| {
+            encoding
+                .as_str()
+                .rsplit(',')
+                .next()
+                .unwrap_or_default()
+                .trim()
+        });
+    if let Some(coding) = final_coding {
+        ensure!(
+            coding.eq_ignore_ascii_case("chunked"),
+            "The final transfer coding should be chunked"
+        );
+    }
+    let framing = match headers.get(&CONTENT_LENGTH).and_then(|v| v.last()) {
+        _ if final_coding.is_some() => Framing::Chunked,
+        Some(length) => {
+            let length = length.as_str().parse::<usize>()?;
+            ensure!(
+                length <= MAX_BODY_LENGTH,
+                "Body byte length should be at most 64mb"
+            );
+            Framing::Length(length)
+        }
+        None => Framing::Length(0),
+    };
+
+    let request = Request {
+        url,
+        method,
+        raw_method: raw_method.to_owned(),
+        raw_target: raw_target.to_owned(),
+        headers,
+        body: Vec::new(),
+        received_at: Instant::now(),
+        matched_mock: None,
+    };
+    Ok(Some((request, framing)))
+}
+
+/// Read the body of `request`, delimited according to `framing`.
+///
+/// If `send_continue` is `true`, `writer` is used to send a `100 Continue` interim response
+/// to clients asking for it before sending the body.  
+/// `received_at` is updated once the body has been read in full.
+async fn read_body<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    request: &mut Request,
+    framing: &Framing,
+    send_continue: bool,
+) -> http_types::Result<()>
+where
+    R: BufRead + Unpin,
+    W: Write + Unpin,
+{
+    // Clients sending `Expect: 100-continue` wait for our go-ahead (or for a timeout) before
+    // sending the body.
+    let expect: HeaderName = "Expect".try_into().unwrap();
+    let expects_continue = request
+        .headers
+        .get(&expect)
+        .and_then(|v| v.last())
+        .map_or(false, |value| {
+            value.as_str().eq_ignore_ascii_case("100-continue")
+        });
+    if send_continue && expects_continue && framing.has_body() {
+        writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").await?;
+        writer.flush().await?;
+    }
+
+    match framing {
+        Framing::Chunked => read_chunked_body(reader, &mut request.body).await?,
+        Framing::Length(length) => {
+            // The buffer grows as bytes come in: we do not trust the declared length to
+            // allocate upfront.
+            let bytes_read = (&mut *reader)
+                .take(*length as u64)
+                .read_to_end(&mut request.body)
+                .await?;
+            if bytes_read < *length {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+        }
+    }
+    request.received_at = Instant::now();
+    Ok(())
+}
+
+/// Read and discard a body delimited according to `framing`.
+async fn skip_body<R>(reader: &mut R, framing: &Framing) -> http_types::Result<()>
+where
+    R: BufRead + Unpin,
+{
+    match framing {
+        Framing::Chunked => read_chunked_body(reader, &mut Vec::new()).await,
+        Framing::Length(length) => {
+            io::copy(&mut reader.take(*length as u64), &mut io::sink()).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Write `response` to `writer`, following the wire-level directives (e.g. `Throughput`)
+/// found in its local state.
+async fn respond<W>(
+    writer: &mut W,
+    mut response: Response,
+    omit_body: bool,
+    http_1_0: bool,
+) -> io::Result<()>
+where
+    W: Write + Unpin,
+{
+    let throughput = response.local_mut().remove::<Throughput>();
+    let (head, body) = encode(response, omit_body, http_1_0).await?;
+    writer.write_all(&head).await?;
+    match throughput {
+        None => writer.write_all(&body).await?,
+        Some(throughput) => write_throttled(writer, &body, throughput.0).await?,
+    }
+    writer.flush().await
+}
+
+/// Read a body sent with `Transfer-Encoding: chunked` into `body`.
+///
+/// Chunk extensions and request trailers are discarded.  
+/// Bodies larger than `MAX_BODY_LENGTH` are rejected.
+async fn read_chunked_body<R>(reader: &mut R, body: &mut Vec<u8>) -> http_types::Result<()>
+where
+    R: BufRead + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let size = line.trim_end().split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| format_err!("Invalid chunk size: {:?}", size))?;
+
+        if size == 0 {
+            // Skip trailers, if any, up to the empty line terminating the body.
+            loop {
+                line.clear();
+                let bytes_read = reader.read_line(&mut line).await?;
+                if bytes_read == 0 || line.trim_end().is_empty() {
+                    return Ok(());
+                }
+            }
+        }
+
+        let end = body.len().checked_add(size);
+        ensure!(
+            end.map_or(false, |end| end <= MAX_BODY_LENGTH),
+            "Body byte length should be at most 64mb"
+        );
+        let bytes_read = (&mut *reader).take(size as u64).read_to_end(body).await?;
+        if bytes_read < size {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        // Every chunk is terminated by a CRLF.
+        let mut crlf = [0u8; 2];
+        reader.read_exact(&mut crlf).await?;
+        ensure!(&crlf == b"\r\n", "Malformed chunk");
+    }
+}
+
+/// Serialise `response` to the bytes we need to write on the wire: the head and what follows
+/// it (the body, with its chunked framing and trailers if any).
+///
+/// Responses are sent with a `Content-Length` header, unless they carry [`Trailers`]
+/// (in their local state): trailers require `Transfer-Encoding: chunked`.  
+/// `DeclaredContentLength` overrides both.
+///
+/// If `omit_body` is `true` (i.e. we are answering a `HEAD` request) the head is left
+/// untouched - `Content-Length` included - but neither the body nor the trailers are sent.
+///
+/// If `http_1_0` is `true` the response is sent as HTTP/1.0, which knows nothing about
+/// chunked encoding: trailers are dropped.
+///
+/// [`Trailers`]: https://docs.rs/http-types/1.2.0/http_types/struct.Trailers.html
+async fn encode(
+    mut response: Response,
+    omit_body: bool,
+    http_1_0: bool,
+) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut body = Vec::new();
+    response
+        .take_body()
+        .into_reader()
+        .read_to_end(&mut body)
+        .await?;
+    let declared_content_length = response.local_mut().remove::<DeclaredContentLength>();
+    // A corrupt `Content-Length` only makes sense without chunked encoding.
+    let trailers = match declared_content_length {
+        Some(_) => None,
+        None if http_1_0 => None,
+        None => response.local_mut().remove::<Trailers>(),
+    };
+
+    let status = response.status();
+    let version = if http_1_0 { "HTTP/1.0" } else { "HTTP/1.1" };
+    let mut head = format!("{} {} {}\r\n", version, status, status.canonical_reason());
+    match &trailers {
+        None => {
+            let content_length = declared_content_length.map_or(body.len(), |l| l.0);
+            head.push_str(&format!("content-length: {}\r\n", content_length))
+        }
+        Some(trailers) => {
+            head.push_str("transfer-encoding: chunked\r\n");
+            let names = trailers
+                .names()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>();
+            head.push_str(&format!("trailer: {}\r\n", names.join(", ")));
+        }
+    }
+    head.push_str(&format!(
+        "date: {}\r\n",
+        httpdate::fmt_http_date(SystemTime::now())
+    ));
+    let order = response
+        .local_mut()
+        .remove::<HeaderOrder>()
+        .map_or_else(Vec::new, |order| order.0);
+    let mut unordered: Vec<&HeaderName> = response
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| !order.contains(name))
+        .collect();
+    unordered.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    for name in order.iter().chain(unordered) {
+        if let Some(values) = response.header(name) {
+            for value in values.iter() {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+    }
+    head.push_str("\r\n");
+
+    let head = head.into_bytes();
+    let mut encoded = Vec::new();
+    if omit_body {
+        return Ok((head, encoded));
+    }
+    match &trailers {
+        None => encoded.extend_from_slice(&body),
+        Some(trailers) => {
+            // We send the whole body as a single chunk.
+            if !body.is_empty() {
+                encoded.extend_from_slice(format!("{:X}\r\n", body.len()).as_bytes());
+                encoded.extend_from_slice(&body);
+                encoded.extend_from_slice(b"\r\n");
+            }
+            encoded.extend_from_slice(b"0\r\n");
+            for (name, values) in trailers.iter() {
+                for value in values.iter() {
+                    encoded.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+                }
+            }
+            encoded.extend_from_slice(b"\r\n");
+        }
+    }
+    Ok((head, encoded))
+}
+
+/// Write `bytes` to `writer` at `bytes_per_sec`.
+///
+/// `bytes` are split in slices worth a tenth of a second each: for each slice, we wait for its
+/// share of time to elapse (as if it were travelling on a slow link) and then write it.
+async fn write_throttled<W>(writer: &mut W, bytes: &[u8], bytes_per_sec: u64) -> io::Result<()>
+where
+    W: Write + Unpin,
+{
+    let slice_length = std::cmp::max(bytes_per_sec / 10, 1) as usize;
+    for slice in bytes.chunks(slice_length) {
+        let seconds = slice.len() as f64 / bytes_per_sec as f64;
+        Delay::new(Duration::from_secs_f64(seconds)).await;
+        writer.write_all(slice).await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Decoding of request bodies sent with a `Content-Encoding` header.
+//!
+//! Each codec is gated behind its own feature: `gzip`, `deflate` and `brotli`.
+use crate::Request;
+use http_types::headers::HeaderName;
+use log::debug;
+use std::convert::TryInto;
+
+/// Replace the body of `request` with its decoded version, according to its
+/// `Content-Encoding` header.
+///
+/// The body is left untouched if one of the encodings is not supported (e.g. because the
+/// corresponding feature is not enabled) or if decoding fails.
+pub(crate) fn decode_body(request: &mut Request) {
+    let header_name: HeaderName = "Content-Encoding".try_into().unwrap();
+    let encodings: Vec<String> = match request.headers.get(&header_name) {
+        Some(values) => values
+            .iter()
+            .flat_map(|value| value.as_str().split(','))
+            .map(|encoding| encoding.trim().to_ascii_lowercase())
+            .filter(|encoding| !encoding.is_empty())
+            .collect(),
+        None => return,
+    };
+
+    let mut body = request.body.clone();
+    // Encodings are listed in the order they were applied: we undo them backwards.
+    for encoding in encodings.iter().rev() {
+        match decode(encoding, &body) {
+            Ok(decoded) => body = decoded,
+            Err(e) => {
+                debug!("Leaving the request body encoded ({}): {}", encoding, e);
+                return;
+            }
+        }
+    }
+    request.body = body;
+}
+
+fn decode(encoding: &str, body: &[u8]) -> Result<Vec<u8>, String> {
+    match encoding {
+        "identity" => Ok(body.to_vec()),
+        #[cfg(feature = "gzip")]
+        "gzip" | "x-gzip" => read_to_end(flate2::read::GzDecoder::new(body)),
+        // `deflate` is supposed to be zlib-wrapped, but some clients send raw deflate data.
+        #[cfg(feature = "deflate")]
+        "deflate" => read_to_end(flate2::read::ZlibDecoder::new(body))
+            .or_else(|_| read_to_end(flate2::read::DeflateDecoder::new(body))),
+        #[cfg(feature = "brotli")]
+        "br" => read_to_end(brotli_decompressor::Decompressor::new(body, 4096)),
+        other => Err(format!("unsupported encoding `{}`", other)),
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "deflate", feature = "brotli"))]
+fn read_to_end<R: std::io::Read>(mut reader: R) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    reader
+        .read_to_end(&mut decoded)
+        .map_err(|e| e.to_string())?;
+    Ok(decoded)
+}
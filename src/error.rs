@@ -0,0 +1,57 @@
+use std::fmt;
+
+/// The error type returned by the fallible (`try_*`) APIs of `wiremock`.
+///
+/// Most of `wiremock`'s APIs panic on invalid inputs - it's usually what you want in a test.
+/// The `try_*` variants return an `Error` instead, allowing you to surface the failure
+/// to your callers (e.g. if you are building `ResponseTemplate`s in a helper library
+/// from data you do not control) or to match on the kind of failure.
+///
+/// The panicking APIs use the `Display` representation of these errors as panic message.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The value could not be converted into a valid header name.
+    InvalidHeaderName(String),
+    /// The value could not be converted into a valid header value.
+    InvalidHeaderValue(String),
+    /// The value could not be converted into a valid status code.
+    InvalidStatusCode(String),
+    /// The value could not be converted into a response body.
+    InvalidBody(String),
+    /// The value could not be serialized into a response body (e.g. as JSON).
+    BodySerialization(String),
+    /// The `MockServer` could not find an available port to listen on.
+    ///
+    /// It carries the error returned when binding the last port that was tried, also
+    /// available via `source`.
+    BindFailed(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHeaderName(e) => write!(f, "Failed to convert into header name: {}", e),
+            Error::InvalidHeaderValue(e) => {
+                write!(f, "Failed to convert into header value: {}", e)
+            }
+            Error::InvalidStatusCode(e) => write!(f, "Failed to convert into status code: {}", e),
+            Error::InvalidBody(e) => write!(f, "Failed to convert into body: {}", e),
+            Error::BodySerialization(e) => write!(f, "Failed to serialize the body: {}", e),
+            Error::BindFailed(e) => write!(
+                f,
+                "Cannot start an HTTP mock server, no free port between 8000 and 8999: {}",
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::BindFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Spans emitted while handling requests, if the `tracing` feature is enabled.
+//!
+//! When the feature is disabled `RequestSpan` is a zero-sized type and all its methods are
+//! no-ops: the connection layer and the mock actor can call them unconditionally.
+use crate::active_mock::ActiveMock;
+use crate::Request;
+use http_types::StatusCode;
+use std::future::Future;
+
+/// The span covering the handling of a single request, from the moment its head has been
+/// read to the moment the response has been written on the wire.
+///
+/// It records the method and the path of the request, the name of the mock that matched it
+/// (if any) and the status code of the response.
+/// Each stage - reading the body, matching, generating the response and writing it - gets
+/// its own child span.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Debug)]
+pub(crate) struct RequestSpan(tracing::Span);
+
+#[cfg(feature = "tracing")]
+impl RequestSpan {
+    /// Open the span of a request whose head has just been read.
+    pub(crate) fn new(request: &Request) -> Self {
+        Self(tracing::info_span!(
+            "wiremock_request",
+            method = %request.raw_method,
+            path = %request.url.path(),
+            matched_mock = tracing::field::Empty,
+            status = tracing::field::Empty,
+        ))
+    }
+
+    /// Run `f`, reading the body of the request, in a child span.
+    pub(crate) fn receiving<F: Future>(&self, f: F) -> impl Future<Output = F::Output> {
+        use tracing::Instrument;
+
+        f.instrument(tracing::debug_span!(parent: &self.0, "read_body"))
+    }
+
+    /// Run `f`, evaluating the matchers of the mounted mocks, in a child span.
+    pub(crate) fn matching<T>(&self, f: impl FnOnce() -> T) -> T {
+        tracing::debug_span!(parent: &self.0, "match_mocks").in_scope(f)
+    }
+
+    /// Run `f`, generating the response, in a child span.
+    pub(crate) fn responding<T>(&self, f: impl FnOnce() -> T) -> T {
+        tracing::debug_span!(parent: &self.0, "generate_response").in_scope(f)
+    }
+
+    /// Run `f`, encoding the response and writing it on the wire, in a child span.
+    pub(crate) fn writing<F: Future>(&self, f: F) -> impl Future<Output = F::Output> {
+        use tracing::Instrument;
+
+        f.instrument(tracing::debug_span!(parent: &self.0, "write_response"))
+    }
+
+    pub(crate) fn record_match(&self, mock: &ActiveMock) {
+        self.0.record("matched_mock", mock.label().as_str());
+    }
+
+    pub(crate) fn record_status(&self, status: StatusCode) {
+        self.0.record("status", u16::from(status));
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[derive(Clone, Debug)]
+pub(crate) struct RequestSpan;
+
+#[cfg(not(feature = "tracing"))]
+impl RequestSpan {
+    #[inline]
+    pub(crate) fn new(_request: &Request) -> Self {
+        Self
+    }
+
+    #[inline]
+    pub(crate) fn receiving<F: Future>(&self, f: F) -> impl Future<Output = F::Output> {
+        f
+    }
+
+    #[inline]
+    pub(crate) fn matching<T>(&self, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    #[inline]
+    pub(crate) fn responding<T>(&self, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    #[inline]
+    pub(crate) fn writing<F: Future>(&self, f: F) -> impl Future<Output = F::Output> {
+        f
+    }
+
+    #[inline]
+    pub(crate) fn record_match(&self, _mock: &ActiveMock) {}
+
+    #[inline]
+    pub(crate) fn record_status(&self, _status: StatusCode) {}
+}
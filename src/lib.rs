@@ -7,11 +7,12 @@
 //! # Table of Contents
 //! 1. [Getting started](#getting-started)
 //! 2. [Matchers](#matchers)
-//! 3. [Spying](#spying)
-//! 4. [Test isolation](#test-isolation)
-//! 5. [Runtime compatibility](#runtime-compatibility)
-//! 6. [Prior art](#prior-art)
-//! 7. [Future evolution](#future-evolution)
+//! 3. [Responders](#responders)
+//! 4. [Spying](#spying)
+//! 5. [Test isolation](#test-isolation)
+//! 6. [Runtime compatibility](#runtime-compatibility)
+//! 7. [Prior art](#prior-art)
+//! 8. [Future evolution](#future-evolution)
 //!
 //! ## Getting started
 //! ```rust
@@ -56,6 +57,12 @@
 //! You can define your own matchers using the [`Match`] trait, as well as using `Fn` closures.  
 //! Check [`Match`]'s documentation for more details and examples.
 //!
+//! ## Responders
+//!
+//! A [`ResponseTemplate`] returns the same response to all matching requests.
+//! If you need to compute the response on a per-request basis, check the [`responders`] module
+//! or define your own using the [`Respond`] trait.
+//!
 //! ## Spying
 //!
 //! `wiremock` empowers you to set expectations on the number of invocations to your [`Mock`]s -
@@ -111,24 +118,44 @@
 //! [`MockServer`]: struct.MockServer.html
 //! [`Mock`]: struct.Mock.html
 //! [`Match`]: trait.Match.html
+//! [`Respond`]: trait.Respond.html
+//! [`ResponseTemplate`]: struct.ResponseTemplate.html
 //! [`start`]: struct.MockServer.html#method.start
 //! [`expect`]: struct.Mock.html#method.expect
 //! [`matchers`]: matchers/index.html
+//! [`responders`]: responders/index.html
 //! [GitHub repository]: https://github.com/LukeMathWalker/wiremock-rs
 //! [`mockito`]: https://docs.rs/mockito/
 //! [`httpmock`]: https://docs.rs/httpmock/
 //! [`async_std`]: https://docs.rs/async-std/
 //! [`tokio`]: https://docs.rs/tokio/
 mod active_mock;
+mod connection;
+mod content_encoding;
+mod error;
+mod instrumentation;
+mod liveness;
 pub mod matchers;
 mod mock;
 mod mock_actor;
+mod mock_guard;
 mod mock_server;
+mod mock_server_builder;
 mod request;
+mod respond;
+pub mod responders;
 mod response_template;
 mod server_actor;
+mod shared_mock_server;
+mod test_scope;
 
-pub use mock::{Match, Mock, MockBuilder, Times};
+pub use error::Error;
+pub use mock::{Match, Mock, MockBuilder, MockSummary, Times};
+pub use mock_guard::MockGuard;
 pub use mock_server::MockServer;
+pub use mock_server_builder::MockServerBuilder;
 pub use request::Request;
+pub use respond::Respond;
 pub use response_template::ResponseTemplate;
+pub use shared_mock_server::{LazyMockServer, SharedMockServer};
+pub use test_scope::TestScope;
@@ -0,0 +1,35 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag tracking whether a background task of the `MockServer` is still alive.
+///
+/// It starts as `true` and it is flipped to `false`, for good, as soon as the `LivenessGuard`
+/// held by the task is dropped - either because the task completed or because it panicked
+/// (guards are dropped while unwinding).  
+/// It never goes back to `true`: even if the task were to be restarted, it would have lost
+/// its state (e.g. the mounted mocks).
+#[derive(Clone, Debug)]
+pub(crate) struct Liveness(Arc<AtomicBool>);
+
+impl Liveness {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(AtomicBool::new(true)))
+    }
+
+    pub(crate) fn is_alive(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Get a guard marking the task as dead when dropped.
+    pub(crate) fn guard(&self) -> LivenessGuard {
+        LivenessGuard(self.0.clone())
+    }
+}
+
+pub(crate) struct LivenessGuard(Arc<AtomicBool>);
+
+impl Drop for LivenessGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
@@ -10,6 +10,7 @@
 //!
 //! [`Match`]: ../trait.Match.html
 //! [`Request`]: ../struct.Request.html
+use crate::mock::debug_name;
 use crate::{Match, Request};
 use http_types::headers::{HeaderName, HeaderValue};
 use http_types::Method;
@@ -17,7 +18,9 @@ use log::debug;
 use regex::Regex;
 use serde::Serialize;
 use std::convert::TryInto;
-use std::str;
+use std::fmt::{Debug, Formatter};
+use std::str::{self, FromStr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Implement the `Match` trait for all closures, out of the box,
 /// if their signature is compatible.
@@ -35,8 +38,14 @@ where
 #[derive(Debug)]
 /// Match **exactly** the method of a request.
 ///
+/// The method can be specified as an `http_types::Method` - typos are caught at compile
+/// time - or as a string. Strings are compared, case-sensitively, with the method as it
+/// appears in the request line: use them for custom or extension methods (e.g. WebDAV's
+/// `PROPFIND`), see [`IntoMethod`].
+///
 /// ### Example:
 /// ```rust
+/// use http_types::Method;
 /// use wiremock::{MockServer, Mock, ResponseTemplate};
 /// use wiremock::matchers::method;
 ///
@@ -46,7 +55,7 @@ where
 ///     let mock_server = MockServer::start().await;
 ///
 ///     let response = ResponseTemplate::new(200);
-///     let mock = Mock::given(method("GET")).respond_with(response);
+///     let mock = Mock::given(method(Method::Get)).respond_with(response);
 ///
 ///     mock_server.register(mock).await;
 ///     
@@ -60,34 +69,177 @@ where
 ///     assert_eq!(status.as_u16(), 200);
 /// }
 /// ```
-pub struct MethodExactMatcher(Method);
+///
+/// [`IntoMethod`]: trait.IntoMethod.html
+pub struct MethodExactMatcher(String);
 
 /// Shorthand for [`MethodExactMatcher::new`](struct.MethodExactMatcher.html).
-pub fn method<T>(method: T) -> MethodExactMatcher
-where
-    T: TryInto<Method>,
-    <T as TryInto<Method>>::Error: std::fmt::Debug,
-{
+pub fn method<T: IntoMethod>(method: T) -> MethodExactMatcher {
     MethodExactMatcher::new(method)
 }
 
 impl MethodExactMatcher {
-    pub fn new<T>(method: T) -> Self
-    where
-        T: TryInto<Method>,
-        <T as TryInto<Method>>::Error: std::fmt::Debug,
-    {
-        let method = method
-            .try_into()
-            .expect("Failed to convert to HTTP method.");
+    /// Specify the expected method, e.g. `Method::Get` or `"GET"`.
+    ///
+    /// It panics if `method` is a standard method spelled with the wrong case (e.g. `"get"`):
+    /// methods are case-sensitive, it would never match.
+    pub fn new<T: IntoMethod>(method: T) -> Self {
+        let method = method.into_raw_method();
+        if let Ok(standard) = Method::from_str(&method.to_ascii_uppercase()) {
+            assert!(
+                method == standard.to_string(),
+                "Failed to convert {:?} into a method: methods are case-sensitive, use `Method::{:?}`",
+                method,
+                standard
+            );
+        }
         Self(method)
     }
 }
 
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for http_types::Method {}
+    impl Sealed for &str {}
+    impl Sealed for String {}
+}
+
+/// The types accepted by [`method`] to specify an HTTP method: `http_types::Method`,
+/// `&str` and `String`.
+///
+/// Prefer `http_types::Method` for standard methods: `method("GTE")` compiles and never
+/// matches, `method(Method::Gte)` does not compile.  
+/// Strings are compared with the method in the request line as they are, which makes them
+/// suitable for custom methods - only standard methods spelled with the wrong case (e.g.
+/// `"get"`) are rejected.
+///
+/// This trait is sealed: it cannot be implemented outside of `wiremock`.
+///
+/// [`method`]: fn.method.html
+pub trait IntoMethod: sealed::Sealed {
+    /// The method, as it appears in the request line.
+    #[doc(hidden)]
+    fn into_raw_method(self) -> String;
+}
+
+impl IntoMethod for Method {
+    fn into_raw_method(self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoMethod for &str {
+    fn into_raw_method(self) -> String {
+        self.to_owned()
+    }
+}
+
+impl IntoMethod for String {
+    fn into_raw_method(self) -> String {
+        self
+    }
+}
+
 impl Match for MethodExactMatcher {
     fn matches(&self, request: &Request) -> bool {
-        request.method == self.0
+        // Extension methods have no `Method` counterpart: only the raw method covers both
+        // them and the standard ones.
+        request.raw_method == self.0
+    }
+
+    debug_name!();
+}
+
+/// Match **exactly** the method of a request, as it appears in the request line.
+///
+/// Use it to match custom or extension HTTP methods (e.g. WebDAV's `PROPFIND`) - it is
+/// the same as [`method`] given a string.  
+/// HTTP methods are case-sensitive: so is the comparison.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::method_str;
+/// use async_std::net::TcpStream;
+/// use async_std::prelude::*;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(method_str("PROPFIND"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let mut stream = TcpStream::connect(mock_server.address()).await.unwrap();
+///     stream
+///         .write_all(b"PROPFIND /files HTTP/1.1\r\nHost: localhost\r\n\r\n")
+///         .await
+///         .unwrap();
+///     let mut buffer = [0u8; 1024];
+///     let bytes_read = stream.read(&mut buffer).await.unwrap();
+///
+///     // Assert
+///     assert!(buffer[..bytes_read].starts_with(b"HTTP/1.1 200 OK"));
+/// }
+/// ```
+///
+/// [`method`]: fn.method.html
+pub fn method_str<T: Into<String>>(method: T) -> MethodExactMatcher {
+    MethodExactMatcher::new(method.into())
+}
+
+#[derive(Debug)]
+/// Match CORS preflight requests: `OPTIONS` requests with an `Access-Control-Request-Method`
+/// header.
+///
+/// Pair it with [`ResponseTemplate::cors_preflight`] to let browser-facing clients through.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::is_cors_preflight;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(is_cors_preflight())
+///         .respond_with(ResponseTemplate::cors_preflight("*", vec!["PUT"], Vec::<String>::new()))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act - a plain `OPTIONS` request is not a preflight
+///     let status = surf::options(&mock_server.uri())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 404);
+/// }
+/// ```
+///
+/// [`ResponseTemplate::cors_preflight`]: ../struct.ResponseTemplate.html#method.cors_preflight
+pub struct CorsPreflightMatcher;
+
+/// Shorthand for [`CorsPreflightMatcher`](struct.CorsPreflightMatcher.html).
+pub fn is_cors_preflight() -> CorsPreflightMatcher {
+    CorsPreflightMatcher
+}
+
+impl Match for CorsPreflightMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let header_name: HeaderName = "Access-Control-Request-Method".try_into().unwrap();
+        request.method == Some(Method::Options) && request.headers.contains_key(&header_name)
     }
+
+    debug_name!();
 }
 
 #[derive(Debug)]
@@ -174,6 +326,8 @@ impl Match for PathExactMatcher {
     fn matches(&self, request: &Request) -> bool {
         request.url.path() == self.0
     }
+
+    debug_name!();
 }
 
 #[derive(Debug)]
@@ -252,6 +406,251 @@ impl Match for PathRegexMatcher {
     fn matches(&self, request: &Request) -> bool {
         self.0.is_match(request.url.path())
     }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the path of a request against a glob pattern.
+///
+/// The pattern is split into `/`-separated segments, each compared to the corresponding
+/// segment of the request path:
+/// - `*` matches exactly one (non-empty) segment;
+/// - `**` matches any number of segments, including none;
+/// - any other segment must match **exactly** - wildcards are only recognised as whole segments.
+///
+/// `*` never crosses a `/`: use [`path_regex`] if you need more flexibility.  
+/// Like [`path`], it ignores query parameters and prepends a `/` to the pattern if missing.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::path_glob;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(path_glob("/users/*/posts"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///     Mock::given(path_glob("/static/**"))
+///         .respond_with(ResponseTemplate::new(204))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let posts = surf::get(format!("{}/users/42/posts", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///     let nested = surf::get(format!("{}/users/42/drafts/posts", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///     let asset = surf::get(format!("{}/static/css/main.css", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(posts.as_u16(), 200);
+///     assert_eq!(nested.as_u16(), 404);
+///     assert_eq!(asset.as_u16(), 204);
+/// }
+/// ```
+///
+/// [`path`]: fn.path.html
+/// [`path_regex`]: fn.path_regex.html
+pub struct PathGlobMatcher(Vec<GlobSegment>);
+
+#[derive(Debug)]
+enum GlobSegment {
+    Literal(String),
+    Any,
+    AnyDeep,
+}
+
+/// Shorthand for [`PathGlobMatcher::new`](struct.PathGlobMatcher.html).
+pub fn path_glob<T>(pattern: T) -> PathGlobMatcher
+where
+    T: Into<String>,
+{
+    PathGlobMatcher::new(pattern)
+}
+
+impl PathGlobMatcher {
+    pub fn new<T: Into<String>>(pattern: T) -> Self {
+        let pattern = pattern.into();
+
+        let segments = pattern
+            .strip_prefix('/')
+            .unwrap_or(&pattern)
+            .split('/')
+            .map(|segment| match segment {
+                "*" => GlobSegment::Any,
+                "**" => GlobSegment::AnyDeep,
+                literal => GlobSegment::Literal(literal.to_owned()),
+            })
+            .collect();
+        Self(segments)
+    }
+}
+
+/// Match `path` segment by segment, backtracking on `**`.
+fn glob_matches(pattern: &[GlobSegment], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((GlobSegment::AnyDeep, rest)) => {
+            (0..=path.len()).any(|skipped| glob_matches(rest, &path[skipped..]))
+        }
+        Some((segment, rest)) => match path.split_first() {
+            None => false,
+            Some((head, tail)) => {
+                let head_matches = match segment {
+                    GlobSegment::Literal(literal) => literal == head,
+                    _ => !head.is_empty(),
+                };
+                head_matches && glob_matches(rest, tail)
+            }
+        },
+    }
+}
+
+impl Match for PathGlobMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let path = request.url.path();
+        let segments: Vec<&str> = path.strip_prefix('/').unwrap_or(path).split('/').collect();
+        glob_matches(&self.0, &segments)
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the path and the query of a request, together, against an expected
+/// `path?query` string.
+///
+/// Both sides are normalised before being compared:
+/// - a `/` is prepended to the expected path if missing;
+/// - percent-encoded sequences in the path are decoded (e.g. `/caf%C3%A9` matches `/café`);
+/// - query parameters are decoded following the `application/x-www-form-urlencoded` rules
+///   (e.g. `+` stands for a space) and sorted, by key and then by value: their order
+///   does not matter, while their number does (repeated parameters must be repeated in
+///   both);
+/// - a request without a query matches an expected string without `?` (or ending with `?`);
+/// - the fragment (`#...`) of the expected string, if any, is ignored: clients do not send it.
+///
+/// Use [`path`] and [`query_param`] if you want the request to have *at least* some query
+/// parameters, instead of *exactly* the specified ones.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::url;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(url("/search?q=wiremock&page=2"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(format!("{}/search?page=2&q=wiremock", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+///
+/// [`path`]: fn.path.html
+/// [`query_param`]: fn.query_param.html
+pub struct UrlMatcher {
+    path: String,
+    query: Vec<(String, String)>,
+}
+
+/// Shorthand for [`UrlMatcher::new`](struct.UrlMatcher.html).
+pub fn url<T>(expected: T) -> UrlMatcher
+where
+    T: Into<String>,
+{
+    UrlMatcher::new(expected)
+}
+
+impl UrlMatcher {
+    pub fn new<T: Into<String>>(expected: T) -> Self {
+        let expected = expected.into();
+        let expected = match expected.find('#') {
+            Some(fragment_start) => &expected[..fragment_start],
+            None => expected.as_str(),
+        };
+        let (path, query) = match expected.find('?') {
+            Some(query_start) => (&expected[..query_start], &expected[query_start + 1..]),
+            None => (expected, ""),
+        };
+        let path = if path.starts_with('/') {
+            path.to_owned()
+        } else {
+            format!("/{}", path)
+        };
+
+        // Let `Url` take care of decoding the query.
+        let parsed = http_types::Url::parse(&format!("http://localhost/?{}", query))
+            .expect("Failed to parse the expected query");
+        Self {
+            path: percent_decode(&path),
+            query: sorted_query_pairs(&parsed),
+        }
+    }
+}
+
+/// Decode the percent-encoded sequences in `input`, leaving malformed ones as they are.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn sorted_query_pairs(url: &http_types::Url) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    pairs.sort();
+    pairs
+}
+
+impl Match for UrlMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        percent_decode(request.url.path()) == self.path
+            && sorted_query_pairs(&request.url) == self.query
+    }
+
+    debug_name!();
 }
 
 #[derive(Debug)]
@@ -319,29 +718,38 @@ impl Match for HeaderExactMatcher {
             Some(values) => values.contains(&self.1),
         }
     }
+
+    debug_name!();
 }
 
 #[derive(Debug)]
-/// Match **exactly** the body of a request.
+/// Match the number of times a header appears in a request.
 ///
-/// ### Example (string):
+/// Every occurrence of the header in the request head is counted separately, even if
+/// they share the same value: `X-Forwarded-For` sent twice has a count of 2.  
+/// Values joined with a comma in a single header line (e.g. `Accept: a, b`) count as one.  
+/// A count of 0 matches requests without the header.
+///
+/// It is handy to detect request smuggling attempts, e.g. duplicate `Content-Length` headers.
+///
+/// ### Example:
 /// ```rust
 /// use wiremock::{MockServer, Mock, ResponseTemplate};
-/// use wiremock::matchers::body_string;
+/// use wiremock::matchers::header_count;
 ///
 /// #[async_std::main]
 /// async fn main() {
 ///     // Arrange
 ///     let mock_server = MockServer::start().await;
 ///
-///     Mock::given(body_string("hello world!"))
+///     Mock::given(header_count("x-api-key", 1))
 ///         .respond_with(ResponseTemplate::new(200))
 ///         .mount(&mock_server)
 ///         .await;
 ///
 ///     // Act
-///     let status = surf::post(&mock_server.uri())
-///         .body_string("hello world!".into())
+///     let status = surf::get(&mock_server.uri())
+///         .set_header("x-api-key", "secret")
 ///         .await
 ///         .unwrap()
 ///         .status();
@@ -350,30 +758,70 @@ impl Match for HeaderExactMatcher {
 ///     assert_eq!(status.as_u16(), 200);
 /// }
 /// ```
+pub struct HeaderCountMatcher(HeaderName, usize);
+
+/// Shorthand for [`HeaderCountMatcher::new`](struct.HeaderCountMatcher.html).
+pub fn header_count<K>(key: K, count: usize) -> HeaderCountMatcher
+where
+    K: TryInto<HeaderName>,
+    <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+{
+    HeaderCountMatcher::new(key, count)
+}
+
+impl HeaderCountMatcher {
+    /// Specify the header name and how many times it is expected to appear.
+    pub fn new<K>(key: K, count: usize) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+    {
+        let key = key.try_into().expect("Failed to convert to header name.");
+        Self(key, count)
+    }
+}
+
+impl Match for HeaderCountMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let count = request
+            .headers
+            .get(&self.0)
+            .map_or(0, |values| values.len());
+        count == self.1
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the media type of the body of a request, as specified in its `Content-Type` header.
 ///
-/// ### Example (json):
+/// Only the type and the subtype are compared, case-insensitively: parameters (e.g. `charset`)
+/// are ignored on both sides.  
+/// `content_type("application/json")` matches a request sent with
+/// `Content-Type: application/json; charset=utf-8`, which the [`header`] matcher would reject.
+///
+/// Requests without a `Content-Type` header never match.
+///
+/// ### Example:
 /// ```rust
 /// use wiremock::{MockServer, Mock, ResponseTemplate};
-/// use wiremock::matchers::body_json;
-/// use serde_json::json;
+/// use wiremock::matchers::content_type;
 ///
 /// #[async_std::main]
 /// async fn main() {
 ///     // Arrange
 ///     let mock_server = MockServer::start().await;
 ///
-///     let expected_body = json!({
-///         "hello": "world!"
-///     });
-///     Mock::given(body_json(&expected_body))
+///     Mock::given(content_type("application/json"))
 ///         .respond_with(ResponseTemplate::new(200))
 ///         .mount(&mock_server)
 ///         .await;
 ///
 ///     // Act
 ///     let status = surf::post(&mock_server.uri())
-///         .body_json(&expected_body)
-///         .unwrap()
+///         .body_string("{}".into())
+///         .set_header("Content-Type", "application/json; charset=utf-8")
 ///         .await
 ///         .unwrap()
 ///         .status();
@@ -382,17 +830,127 @@ impl Match for HeaderExactMatcher {
 ///     assert_eq!(status.as_u16(), 200);
 /// }
 /// ```
-pub struct BodyExactMatcher(Vec<u8>);
+///
+/// [`header`]: fn.header.html
+pub struct ContentTypeMatcher(String);
 
-impl BodyExactMatcher {
-    /// Specify the expected body as a string.
-    pub fn string<T: Into<String>>(body: T) -> Self {
-        let body = body.into();
-        Self(body.as_bytes().into())
+/// Shorthand for [`ContentTypeMatcher::new`](struct.ContentTypeMatcher.html).
+pub fn content_type<T>(mime: T) -> ContentTypeMatcher
+where
+    T: Into<String>,
+{
+    ContentTypeMatcher::new(mime)
+}
+
+impl ContentTypeMatcher {
+    /// Specify the expected media type, e.g. `application/json`.
+    ///
+    /// It panics if `mime` is not a valid media type.
+    pub fn new<T: Into<String>>(mime: T) -> Self {
+        let mime = mime.into();
+        let essence = media_type_essence(&mime).expect("Failed to parse media type.");
+        Self(essence)
     }
+}
 
-    /// Specify the expected body as a vector of bytes.
-    pub fn bytes<T: Into<Vec<u8>>>(body: T) -> Self {
+/// Extract the `type/subtype` part of a media type, lowercased, dropping its parameters.
+fn media_type_essence(mime: &str) -> Option<String> {
+    let essence = mime.split(';').next()?.trim().to_ascii_lowercase();
+    let mut parts = essence.splitn(2, '/');
+    match (parts.next(), parts.next()) {
+        (Some(basetype), Some(subtype)) if !basetype.is_empty() && !subtype.is_empty() => {
+            Some(essence)
+        }
+        _ => None,
+    }
+}
+
+impl Match for ContentTypeMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let header_name: HeaderName = "Content-Type".try_into().unwrap();
+        request
+            .headers
+            .get(&header_name)
+            .and_then(|values| values.last())
+            .and_then(|value| media_type_essence(value.as_str()))
+            .map_or(false, |essence| essence == self.0)
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match **exactly** the body of a request.
+///
+/// ### Example (string):
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::body_string;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(body_string("hello world!"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_string("hello world!".into())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+///
+/// ### Example (json):
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::body_json;
+/// use serde_json::json;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     let expected_body = json!({
+///         "hello": "world!"
+///     });
+///     Mock::given(body_json(&expected_body))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_json(&expected_body)
+///         .unwrap()
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+pub struct BodyExactMatcher(Vec<u8>);
+
+impl BodyExactMatcher {
+    /// Specify the expected body as a string.
+    pub fn string<T: Into<String>>(body: T) -> Self {
+        let body = body.into();
+        Self(body.as_bytes().into())
+    }
+
+    /// Specify the expected body as a vector of bytes.
+    pub fn bytes<T: Into<Vec<u8>>>(body: T) -> Self {
         let body = body.into();
         Self(body)
     }
@@ -432,6 +990,192 @@ impl Match for BodyExactMatcher {
     fn matches(&self, request: &Request) -> bool {
         request.body == self.0
     }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the body of a request, parsed as JSON, against an expected JSON value, ignoring
+/// the order of array elements.
+///
+/// [`body_json`] already ignores the order of the keys of JSON objects, but array elements
+/// must appear in the same order.  
+/// `body_json_unordered_arrays` compares arrays as multisets: they match if they contain the
+/// same elements, the same number of times, in any order. It applies at every level of
+/// nesting (e.g. arrays inside objects inside arrays).  
+/// It comes in handy when your client serializes a set, or a hash map into an array, in
+/// a nondeterministic order.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::body_json_unordered_arrays;
+/// use serde_json::json;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(body_json_unordered_arrays(json!({ "tags": ["a", "b", "c"] })))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_json(&json!({ "tags": ["c", "a", "b"] }))
+///         .unwrap()
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+///
+/// [`body_json`]: fn.body_json.html
+pub struct BodyJsonUnorderedArraysMatcher(serde_json::Value);
+
+impl BodyJsonUnorderedArraysMatcher {
+    /// Specify something JSON-serializable as the expected body.
+    pub fn new<T: Serialize>(body: T) -> Self {
+        let body = serde_json::to_value(&body).expect("Failed to serialise body");
+        Self(body)
+    }
+}
+
+/// Shorthand for [`BodyJsonUnorderedArraysMatcher::new`](struct.BodyJsonUnorderedArraysMatcher.html).
+pub fn body_json_unordered_arrays<T>(body: T) -> BodyJsonUnorderedArraysMatcher
+where
+    T: Serialize,
+{
+    BodyJsonUnorderedArraysMatcher::new(body)
+}
+
+/// Compare two JSON values, treating arrays as multisets.
+///
+/// It is an equivalence relation: we can pair the elements of two arrays greedily.
+fn json_eq_unordered(expected: &serde_json::Value, actual: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    match (expected, actual) {
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.len() != actual.len() {
+                return false;
+            }
+            let mut paired = vec![false; actual.len()];
+            expected.iter().all(|e| {
+                let pair =
+                    (0..actual.len()).find(|&i| !paired[i] && json_eq_unordered(e, &actual[i]));
+                match pair {
+                    Some(i) => {
+                        paired[i] = true;
+                        true
+                    }
+                    None => false,
+                }
+            })
+        }
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .all(|(key, e)| actual.get(key).map_or(false, |a| json_eq_unordered(e, a)))
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+impl Match for BodyJsonUnorderedArraysMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        match serde_json::from_slice::<serde_json::Value>(&request.body) {
+            Ok(body) => json_eq_unordered(&self.0, &body),
+            Err(err) => {
+                debug!("can't parse body as JSON: {}", err);
+                false
+            }
+        }
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match [JSON-RPC 2.0] requests calling the method `name`.
+///
+/// The body must be a JSON-RPC request object: a JSON object with a `jsonrpc` member set to
+/// `"2.0"` and a `method` member set to `name`. Notifications (requests without an `id`)
+/// match as well.  
+/// Batch requests (a JSON array of request objects) never match, regardless of the methods
+/// they call: use [`body_json_unordered_arrays`] or a custom matcher to handle them.
+///
+/// Pair it with [`ResponseTemplate::jsonrpc_result`] and [`ResponseTemplate::jsonrpc_error`]
+/// to build the response.
+///
+/// ### Example:
+/// ```rust
+/// use serde_json::json;
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{jsonrpc_method, method};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(method("POST"))
+///         .and(jsonrpc_method("subtract"))
+///         .respond_with(ResponseTemplate::jsonrpc_result(1, 19))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let body = json!({"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1});
+///     let status = surf::post(&mock_server.uri())
+///         .body_json(&body)
+///         .unwrap()
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+///
+/// [JSON-RPC 2.0]: https://www.jsonrpc.org/specification
+/// [`body_json_unordered_arrays`]: fn.body_json_unordered_arrays.html
+/// [`ResponseTemplate::jsonrpc_result`]: ../struct.ResponseTemplate.html#method.jsonrpc_result
+/// [`ResponseTemplate::jsonrpc_error`]: ../struct.ResponseTemplate.html#method.jsonrpc_error
+pub struct JsonRpcMethodMatcher(String);
+
+impl JsonRpcMethodMatcher {
+    /// Specify the name of the method the request must call.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self(name.into())
+    }
+}
+
+/// Shorthand for [`JsonRpcMethodMatcher::new`](struct.JsonRpcMethodMatcher.html).
+pub fn jsonrpc_method<T: Into<String>>(name: T) -> JsonRpcMethodMatcher {
+    JsonRpcMethodMatcher::new(name)
+}
+
+impl Match for JsonRpcMethodMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        match serde_json::from_slice::<serde_json::Value>(&request.body) {
+            // Arrays (i.e. batches) have no `jsonrpc` member: they fall through.
+            Ok(body) => body["jsonrpc"] == "2.0" && body["method"] == self.0.as_str(),
+            Err(err) => {
+                debug!("can't parse body as JSON: {}", err);
+                false
+            }
+        }
+    }
+
+    debug_name!();
 }
 
 #[derive(Debug)]
@@ -503,28 +1247,33 @@ impl Match for BodyContainsMatcher {
 
         body.contains(part)
     }
+
+    debug_name!();
 }
 
 #[derive(Debug)]
-/// Match **exactly** the query parameter of a request.
+/// Match the beginning of the body of a request, decoded as UTF-8.
+///
+/// Requests whose body is not valid UTF-8 do not match.
 ///
 /// ### Example:
 /// ```rust
 /// use wiremock::{MockServer, Mock, ResponseTemplate};
-/// use wiremock::matchers::query_param;
+/// use wiremock::matchers::body_string_starts_with;
 ///
 /// #[async_std::main]
 /// async fn main() {
 ///     // Arrange
 ///     let mock_server = MockServer::start().await;
 ///
-///     Mock::given(query_param("hello", "world"))
+///     Mock::given(body_string_starts_with("hello"))
 ///         .respond_with(ResponseTemplate::new(200))
 ///         .mount(&mock_server)
 ///         .await;
 ///
 ///     // Act
-///     let status = surf::get(format!("{}?hello=world", &mock_server.uri()))
+///     let status = surf::post(&mock_server.uri())
+///         .body_string("hello world!".into())
 ///         .await
 ///         .unwrap()
 ///         .status();
@@ -533,31 +1282,928 @@ impl Match for BodyContainsMatcher {
 ///     assert_eq!(status.as_u16(), 200);
 /// }
 /// ```
-pub struct QueryParamExactMatcher(String, String);
+pub struct BodyStartsWithMatcher(String);
 
-impl QueryParamExactMatcher {
-    /// Specify the expected value for a query parameter.
-    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Self {
-        let key = key.into();
-        let value = value.into();
-        Self(key, value)
+impl BodyStartsWithMatcher {
+    /// Specify the prefix the body should start with.
+    pub fn string<T: Into<String>>(prefix: T) -> Self {
+        Self(prefix.into())
     }
 }
 
-/// Shorthand for [`QueryParamExactMatcher::new`](struct.QueryParamExactMatcher.html).
-pub fn query_param<K, V>(key: K, value: V) -> QueryParamExactMatcher
+/// Shorthand for [`BodyStartsWithMatcher::string`](struct.BodyStartsWithMatcher.html).
+pub fn body_string_starts_with<T>(prefix: T) -> BodyStartsWithMatcher
 where
-    K: Into<String>,
-    V: Into<String>,
+    T: Into<String>,
 {
-    QueryParamExactMatcher::new(key, value)
+    BodyStartsWithMatcher::string(prefix)
 }
 
-impl Match for QueryParamExactMatcher {
+impl Match for BodyStartsWithMatcher {
     fn matches(&self, request: &Request) -> bool {
-        request
-            .url
-            .query_pairs()
-            .any(|q| q.0 == self.0.as_str() && q.1 == self.1.as_str())
+        match str::from_utf8(&request.body) {
+            Ok(body) => body.starts_with(&self.0),
+            Err(err) => {
+                debug!("can't convert body from byte slice to string: {}", err);
+                false
+            }
+        }
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the end of the body of a request, decoded as UTF-8.
+///
+/// Requests whose body is not valid UTF-8 do not match.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::body_string_ends_with;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(body_string_ends_with("world!"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_string("hello world!".into())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+pub struct BodyEndsWithMatcher(String);
+
+impl BodyEndsWithMatcher {
+    /// Specify the suffix the body should end with.
+    pub fn string<T: Into<String>>(suffix: T) -> Self {
+        Self(suffix.into())
     }
 }
+
+/// Shorthand for [`BodyEndsWithMatcher::string`](struct.BodyEndsWithMatcher.html).
+pub fn body_string_ends_with<T>(suffix: T) -> BodyEndsWithMatcher
+where
+    T: Into<String>,
+{
+    BodyEndsWithMatcher::string(suffix)
+}
+
+impl Match for BodyEndsWithMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        match str::from_utf8(&request.body) {
+            Ok(body) => body.ends_with(&self.0),
+            Err(err) => {
+                debug!("can't convert body from byte slice to string: {}", err);
+                false
+            }
+        }
+    }
+
+    debug_name!();
+}
+
+/// Match the body of a request, parsed as JSON, against a [JSON Schema](https://json-schema.org/).
+///
+/// It matches if the body is valid JSON and it validates against the schema: use it to
+/// enforce the contract agreed with the API your code is talking to, instead of checking
+/// the payload field by field.  
+/// The schema is compiled once, when the matcher is built: it panics if `schema` is not a
+/// valid JSON Schema.
+///
+/// References to external schemas (`$ref` pointing to a URL or a file) are not resolved.
+///
+/// It is only available if the `jsonschema` feature is enabled.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::body_json_matches_schema;
+/// use serde_json::json;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///     let schema = json!({
+///         "type": "object",
+///         "properties": { "name": { "type": "string" } },
+///         "required": ["name"]
+///     });
+///
+///     Mock::given(body_json_matches_schema(schema))
+///         .respond_with(ResponseTemplate::new(201))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_json(&json!({ "name": "wiremock" }))
+///         .unwrap()
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 201);
+/// }
+/// ```
+#[cfg(feature = "jsonschema")]
+pub struct JsonSchemaMatcher(jsonschema::JSONSchema);
+
+#[cfg(feature = "jsonschema")]
+impl JsonSchemaMatcher {
+    /// Compile `schema`, panicking if it is not a valid JSON Schema.
+    pub fn new(schema: serde_json::Value) -> Self {
+        let compiled = jsonschema::JSONSchema::compile(&schema)
+            .unwrap_or_else(|e| panic!("Failed to compile the JSON Schema: {}", e));
+        Self(compiled)
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl Debug for JsonSchemaMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("JsonSchemaMatcher")
+    }
+}
+
+/// Shorthand for [`JsonSchemaMatcher::new`](struct.JsonSchemaMatcher.html).
+#[cfg(feature = "jsonschema")]
+pub fn body_json_matches_schema(schema: serde_json::Value) -> JsonSchemaMatcher {
+    JsonSchemaMatcher::new(schema)
+}
+
+#[cfg(feature = "jsonschema")]
+impl Match for JsonSchemaMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        match serde_json::from_slice::<serde_json::Value>(&request.body) {
+            Ok(body) => self.0.is_valid(&body),
+            Err(err) => {
+                debug!("can't parse body as JSON: {}", err);
+                false
+            }
+        }
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the value of the `Content-Length` header of a request.
+///
+/// It checks what the client **declared**, not the actual length of the body: use
+/// [`body_length_eq`] for that.  
+/// The two can disagree, e.g. when a request is sent with both `Transfer-Encoding: chunked`
+/// and `Content-Length` - the `MockServer` reads the body according to `Transfer-Encoding`,
+/// as mandated by the HTTP specification.
+///
+/// Requests without a `Content-Length` header (or with an invalid one) never match.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::content_length;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(content_length(5))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_string("hello".into())
+///         .set_header("Content-Length", "5")
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+///
+/// [`body_length_eq`]: fn.body_length_eq.html
+pub struct ContentLengthMatcher(u64);
+
+impl ContentLengthMatcher {
+    /// Specify the expected value of the `Content-Length` header.
+    pub fn new(length: u64) -> Self {
+        Self(length)
+    }
+}
+
+/// Shorthand for [`ContentLengthMatcher::new`](struct.ContentLengthMatcher.html).
+pub fn content_length(length: u64) -> ContentLengthMatcher {
+    ContentLengthMatcher::new(length)
+}
+
+impl Match for ContentLengthMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let header_name: HeaderName = "Content-Length".try_into().unwrap();
+        request
+            .headers
+            .get(&header_name)
+            .and_then(|values| values.last())
+            .and_then(|value| value.as_str().trim().parse::<u64>().ok())
+            == Some(self.0)
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the actual length, in bytes, of the body of a request.
+///
+/// It ignores the `Content-Length` header: use [`content_length`] to check what the client
+/// declared.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::body_length_eq;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(body_length_eq(5))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_string("hello".into())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+///
+/// [`content_length`]: fn.content_length.html
+pub struct BodyLengthMatcher(usize);
+
+impl BodyLengthMatcher {
+    /// Specify the expected length of the body, in bytes.
+    pub fn new(length: usize) -> Self {
+        Self(length)
+    }
+}
+
+/// Shorthand for [`BodyLengthMatcher::new`](struct.BodyLengthMatcher.html).
+pub fn body_length_eq(length: usize) -> BodyLengthMatcher {
+    BodyLengthMatcher::new(length)
+}
+
+impl Match for BodyLengthMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        request.body.len() == self.0
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match requests without a body.
+///
+/// Only the bytes received after the head of the request are taken into account: a request
+/// without a body and a request with a zero-length body (e.g. `Content-Length: 0` or an
+/// empty chunked body) are treated the same way. HTTP/1.1 gives us no reliable way to tell
+/// them apart - most clients send `Content-Length: 0` for an empty `POST` and nothing at all
+/// for a `GET`.  
+/// Use [`header`] on `Content-Length` if you need to check what the client declared.
+///
+/// The complement of [`body_is_present`].
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{body_is_empty, method};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(method("DELETE"))
+///         .and(body_is_empty())
+///         .respond_with(ResponseTemplate::new(204))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::delete(&mock_server.uri())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 204);
+/// }
+/// ```
+///
+/// [`header`]: fn.header.html
+/// [`body_is_present`]: fn.body_is_present.html
+pub struct EmptyBodyMatcher;
+
+/// Shorthand for [`EmptyBodyMatcher`](struct.EmptyBodyMatcher.html).
+pub fn body_is_empty() -> EmptyBodyMatcher {
+    EmptyBodyMatcher
+}
+
+impl Match for EmptyBodyMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        request.body.is_empty()
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match requests carrying a body at least one byte long.
+///
+/// The complement of [`body_is_empty`]: see its documentation for how requests without
+/// a body and requests with a zero-length body are handled.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{body_is_present, method};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(method("POST"))
+///         .and(body_is_present())
+///         .respond_with(ResponseTemplate::new(201))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(&mock_server.uri())
+///         .body_string("hello".into())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 201);
+/// }
+/// ```
+///
+/// [`body_is_empty`]: fn.body_is_empty.html
+pub struct BodyPresentMatcher;
+
+/// Shorthand for [`BodyPresentMatcher`](struct.BodyPresentMatcher.html).
+pub fn body_is_present() -> BodyPresentMatcher {
+    BodyPresentMatcher
+}
+
+impl Match for BodyPresentMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        !request.body.is_empty()
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match **exactly** the query parameter of a request.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::query_param;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(query_param("hello", "world"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(format!("{}?hello=world", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+/// }
+/// ```
+pub struct QueryParamExactMatcher(String, String);
+
+impl QueryParamExactMatcher {
+    /// Specify the expected value for a query parameter.
+    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Self {
+        let key = key.into();
+        let value = value.into();
+        Self(key, value)
+    }
+}
+
+/// Shorthand for [`QueryParamExactMatcher::new`](struct.QueryParamExactMatcher.html).
+pub fn query_param<K, V>(key: K, value: V) -> QueryParamExactMatcher
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    QueryParamExactMatcher::new(key, value)
+}
+
+impl Match for QueryParamExactMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        request
+            .url
+            .query_pairs()
+            .any(|q| q.0 == self.0.as_str() && q.1 == self.1.as_str())
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the number of query parameters of a request.
+///
+/// Every occurrence of a key is counted: `?a=1&a=2` has two query parameters.  
+/// Combined with [`query_param`] it lets you pin down the full shape of the query string,
+/// rejecting requests that carry unexpected extra parameters.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{query_param, query_param_count};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(query_param("hello", "world"))
+///         .and(query_param_count(1))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(format!("{}?hello=world&extra=1", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 404);
+/// }
+/// ```
+///
+/// [`query_param`]: fn.query_param.html
+pub struct QueryParamCountMatcher(usize);
+
+impl QueryParamCountMatcher {
+    /// Specify the expected number of query parameters.
+    pub fn new(count: usize) -> Self {
+        Self(count)
+    }
+}
+
+/// Shorthand for [`QueryParamCountMatcher::new`](struct.QueryParamCountMatcher.html).
+pub fn query_param_count(count: usize) -> QueryParamCountMatcher {
+    QueryParamCountMatcher::new(count)
+}
+
+impl Match for QueryParamCountMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        request.url.query_pairs().count() == self.0
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match requests without a query string.
+///
+/// A trailing `?` with nothing after it (e.g. `/x?`) counts as no query string: there are
+/// no parameters to speak of, and most clients cannot tell the two apart anyway.  
+/// It complements [`query_param`] and [`query_param_count`]: use it to check that your
+/// client does not append (e.g. tracking) parameters to a clean endpoint.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{has_no_query, path};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(path("/clean"))
+///         .and(has_no_query())
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(format!("{}/clean?utm_source=test", &mock_server.uri()))
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 404);
+/// }
+/// ```
+///
+/// [`query_param`]: fn.query_param.html
+/// [`query_param_count`]: fn.query_param_count.html
+pub struct NoQueryMatcher;
+
+/// Shorthand for [`NoQueryMatcher`](struct.NoQueryMatcher.html).
+pub fn has_no_query() -> NoQueryMatcher {
+    NoQueryMatcher
+}
+
+impl Match for NoQueryMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        match request.url.query() {
+            Some(query) => query.is_empty(),
+            None => true,
+        }
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the `If-Modified-Since` header of a conditional request against the last modification
+/// time of a resource.
+///
+/// The header value is parsed according to any of the three date formats allowed by
+/// the HTTP specification (IMF-fixdate, RFC 850 and ANSI C's `asctime()`).  
+/// HTTP dates have a one-second resolution: `last_modified` is truncated to the second before
+/// being compared with the header value.
+///
+/// Requests without an `If-Modified-Since` header, or with an invalid date, never match.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{if_modified_since, IfModifiedSinceMatcher};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///     // Sun, 06 Nov 1994 08:49:37 GMT
+///     let last_modified = UNIX_EPOCH + Duration::from_secs(784111777);
+///
+///     // The client's copy is still fresh
+///     Mock::given(if_modified_since(last_modified))
+///         .respond_with(ResponseTemplate::new(304))
+///         .mount(&mock_server)
+///         .await;
+///     // The client's copy is stale
+///     Mock::given(IfModifiedSinceMatcher::modified(last_modified))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(&mock_server.uri())
+///         .set_header("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT")
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 304);
+/// }
+/// ```
+pub struct IfModifiedSinceMatcher {
+    last_modified: SystemTime,
+    // If `true`, match when the resource has been modified after the date in the header.
+    // If `false`, match when it has not.
+    modified: bool,
+}
+
+/// Shorthand for [`IfModifiedSinceMatcher::not_modified`](struct.IfModifiedSinceMatcher.html#method.not_modified).
+pub fn if_modified_since(last_modified: SystemTime) -> IfModifiedSinceMatcher {
+    IfModifiedSinceMatcher::not_modified(last_modified)
+}
+
+impl IfModifiedSinceMatcher {
+    /// Match when the resource has **not** been modified after the date specified in the
+    /// `If-Modified-Since` header - i.e. the client's copy is fresh and a `304 Not Modified`
+    /// is appropriate.
+    pub fn not_modified(last_modified: SystemTime) -> Self {
+        Self {
+            last_modified: truncate_to_seconds(last_modified),
+            modified: false,
+        }
+    }
+
+    /// Match when the resource has been modified after the date specified in the
+    /// `If-Modified-Since` header - i.e. the client's copy is stale.
+    pub fn modified(last_modified: SystemTime) -> Self {
+        Self {
+            last_modified: truncate_to_seconds(last_modified),
+            modified: true,
+        }
+    }
+}
+
+fn truncate_to_seconds(time: SystemTime) -> SystemTime {
+    let since_epoch = time
+        .duration_since(UNIX_EPOCH)
+        .expect("Last modification time cannot be earlier than the UNIX epoch.");
+    UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs())
+}
+
+impl Match for IfModifiedSinceMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let header_name: HeaderName = "If-Modified-Since".try_into().unwrap();
+        let since = match request
+            .headers
+            .get(&header_name)
+            .and_then(|values| values.last())
+            .map(|value| httpdate::parse_http_date(value.as_str()))
+        {
+            Some(Ok(since)) => since,
+            Some(Err(err)) => {
+                debug!("can't parse If-Modified-Since header: {}", err);
+                return false;
+            }
+            None => return false,
+        };
+
+        (self.last_modified > since) == self.modified
+    }
+
+    debug_name!();
+}
+
+#[derive(Debug)]
+/// Match the `If-None-Match` header of a conditional request against the current entity tag
+/// (`ETag`) of a resource.
+///
+/// `etag` is the entity tag as it would appear in an `ETag` header: a quoted string, with a
+/// `W/` prefix for weak validators (e.g. `"xyzzy"` or `W/"xyzzy"`).  
+/// It matches when any of the entity tags listed by the client is equivalent to `etag`, or if
+/// the client sent `If-None-Match: *` - i.e. the client's copy is fresh and a `304 Not Modified`
+/// is appropriate.
+///
+/// Two comparison functions are available, as defined by RFC 7232:
+/// - [`weak`] (the semantics prescribed for `If-None-Match`): two entity tags are equivalent if
+///   their opaque tags match, regardless of either of them being weak;
+/// - [`strong`]: two entity tags are equivalent only if their opaque tags match and neither of
+///   them is weak.
+///
+/// | `etag`      | Header      | Weak comparison | Strong comparison |
+/// |-------------|-------------|-----------------|-------------------|
+/// | `W/"1"`     | `W/"1"`     | match           | no match          |
+/// | `W/"1"`     | `"1"`       | match           | no match          |
+/// | `"1"`       | `"1"`       | match           | match             |
+/// | `"1"`       | `"2"`       | no match        | no match          |
+///
+/// Requests without an `If-None-Match` header never match.  
+/// It panics if `etag` is not a valid entity tag.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::{if_none_match, method};
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     // The client's copy is still fresh
+///     Mock::given(if_none_match(r#"W/"v2""#))
+///         .respond_with(ResponseTemplate::new(304))
+///         .mount(&mock_server)
+///         .await;
+///     // The client's copy is stale (or missing)
+///     Mock::given(method("GET"))
+///         .respond_with(ResponseTemplate::new(200).insert_header("ETag", r#"W/"v2""#))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(&mock_server.uri())
+///         .set_header("If-None-Match", r#""v1", "v2""#)
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 304);
+/// }
+/// ```
+///
+/// [`weak`]: #method.weak
+/// [`strong`]: #method.strong
+pub struct IfNoneMatchMatcher {
+    etag: EntityTag,
+    // If `true`, use the strong comparison function. If `false`, the weak one.
+    strong: bool,
+}
+
+/// Shorthand for [`IfNoneMatchMatcher::weak`](struct.IfNoneMatchMatcher.html#method.weak).
+pub fn if_none_match<T: Into<String>>(etag: T) -> IfNoneMatchMatcher {
+    IfNoneMatchMatcher::weak(etag)
+}
+
+impl IfNoneMatchMatcher {
+    /// Compare entity tags using the weak comparison function: `W/"1"` matches `"1"`.
+    pub fn weak<T: Into<String>>(etag: T) -> Self {
+        Self {
+            etag: EntityTag::parse_one(&etag.into()),
+            strong: false,
+        }
+    }
+
+    /// Compare entity tags using the strong comparison function: weak entity tags never match.
+    pub fn strong<T: Into<String>>(etag: T) -> Self {
+        Self {
+            etag: EntityTag::parse_one(&etag.into()),
+            strong: true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct EntityTag {
+    weak: bool,
+    // The opaque tag, without the surrounding quotes.
+    tag: String,
+}
+
+impl EntityTag {
+    fn parse_one(etag: &str) -> Self {
+        match EntityTag::parse_list(etag) {
+            Some(mut tags) if tags.len() == 1 => tags.remove(0),
+            _ => panic!("{} is not a valid entity tag.", etag),
+        }
+    }
+
+    // Parse a comma-separated list of entity tags (e.g. `"a", W/"b"`).
+    // Opaque tags can contain commas: we can't just split on them.
+    fn parse_list(mut list: &str) -> Option<Vec<Self>> {
+        let mut tags = vec![];
+        loop {
+            list = list.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+            if list.is_empty() {
+                return Some(tags);
+            }
+            let weak = list.starts_with("W/");
+            if weak {
+                list = &list[2..];
+            }
+            let rest = list.strip_prefix('"')?;
+            let end = rest.find('"')?;
+            tags.push(Self {
+                weak,
+                tag: rest[..end].to_owned(),
+            });
+            list = &rest[end + 1..];
+        }
+    }
+
+    fn matches(&self, other: &Self, strong: bool) -> bool {
+        self.tag == other.tag && (!strong || (!self.weak && !other.weak))
+    }
+}
+
+impl Match for IfNoneMatchMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let header_name: HeaderName = "If-None-Match".try_into().unwrap();
+        let values = match request.headers.get(&header_name) {
+            Some(values) => values,
+            None => return false,
+        };
+        values.iter().any(|value| {
+            if value.as_str().trim() == "*" {
+                return true;
+            }
+            match EntityTag::parse_list(value.as_str()) {
+                Some(tags) => tags.iter().any(|tag| tag.matches(&self.etag, self.strong)),
+                None => {
+                    debug!("can't parse If-None-Match header: {}", value);
+                    false
+                }
+            }
+        })
+    }
+
+    debug_name!();
+}
+
+/// Match requests using an arbitrary predicate on the whole [`Request`].
+///
+/// It is the ultimate escape hatch: the predicate can express conditions spanning multiple
+/// fields of the request (method, url, headers and body), which cannot be captured by
+/// combining the other matchers in this module.  
+/// It requires you to work with the public fields of [`Request`] directly.
+///
+/// Closures with the same signature implement [`Match`] on their own: `request_matches`
+/// spells out the intent and gives the matcher a nameable type.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, Request, ResponseTemplate};
+/// use wiremock::matchers::request_matches;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     // A POST request to /upload with a non-empty body.
+///     let matcher = request_matches(|request: &Request| {
+///         request.method == Some(http_types::Method::Post)
+///             && request.url.path() == "/upload"
+///             && !request.body.is_empty()
+///     });
+///     Mock::given(matcher)
+///         .respond_with(ResponseTemplate::new(201))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::post(format!("{}/upload", &mock_server.uri()))
+///         .body_string("data".into())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 201);
+/// }
+/// ```
+///
+/// [`Match`]: ../trait.Match.html
+/// [`Request`]: ../struct.Request.html
+pub struct RequestPredicateMatcher(Box<dyn Fn(&Request) -> bool + Send + Sync>);
+
+/// Shorthand for [`RequestPredicateMatcher::new`](struct.RequestPredicateMatcher.html).
+pub fn request_matches<F>(predicate: F) -> RequestPredicateMatcher
+where
+    F: Fn(&Request) -> bool + Send + Sync + 'static,
+{
+    RequestPredicateMatcher::new(predicate)
+}
+
+impl RequestPredicateMatcher {
+    /// Specify the predicate that incoming requests must satisfy.
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&Request) -> bool + Send + Sync + 'static,
+    {
+        Self(Box::new(predicate))
+    }
+}
+
+impl Debug for RequestPredicateMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Closures do not implement `Debug`.
+        f.write_str("RequestPredicateMatcher")
+    }
+}
+
+impl Match for RequestPredicateMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        (self.0)(request)
+    }
+
+    debug_name!();
+}
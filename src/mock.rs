@@ -1,16 +1,20 @@
+use crate::respond::Responder;
 use crate::response_template::ResponseTemplate;
-use crate::{MockServer, Request};
-use http_types::Response;
+use crate::{MockServer, Request, Respond};
+use http_types::{Method, Response};
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::ops::{
     Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
 };
-use std::time::Duration;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 /// Anything that implements `Match` can be used to constrain when a [`Mock`] is activated.
 ///
-/// `Match` is the only trait in the whole `wiremock` crate and can be used to extend
-/// the set of matchers provided out-of-the-box to cater to your specific testing needs:
+/// `Match` can be used to extend the set of matchers provided out-of-the-box to cater to your
+/// specific testing needs:
 /// ```rust
 /// use wiremock::{Match, MockServer, Mock, Request, ResponseTemplate};
 /// use wiremock::matchers::HeaderExactMatcher;
@@ -109,8 +113,33 @@ pub trait Match: Send + Sync {
     /// Given a reference to a `Request`, determine if it should match or not given
     /// a specific criterion.
     fn matches(&self, request: &Request) -> bool;
+
+    /// A human-readable description of the matcher, used in diagnostics (e.g. by
+    /// [`MockServer::mounted_mocks`]).
+    ///
+    /// It defaults to the name of the type implementing `Match`: override it to tell apart
+    /// different instances of your custom matchers. The matchers provided out-of-the-box
+    /// describe themselves with their `Debug` representation, e.g. `MethodExactMatcher("GET")`.
+    ///
+    /// [`MockServer::mounted_mocks`]: struct.MockServer.html#method.mounted_mocks
+    fn name(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
 }
 
+/// Implement [`Match::name`] with the `Debug` representation of the matcher, as the matchers
+/// provided out-of-the-box do: invoke it inside their `impl Match` block.
+///
+/// [`Match::name`]: trait.Match.html#method.name
+macro_rules! debug_name {
+    () => {
+        fn name(&self) -> String {
+            format!("{:?}", self)
+        }
+    };
+}
+pub(crate) use debug_name;
+
 /// Wrapper around a `Match` trait object.
 ///
 /// We need the wrapper to provide a (fake) implementation of `Debug`,
@@ -125,6 +154,10 @@ impl Match for Matcher {
     fn matches(&self, request: &Request) -> bool {
         self.0.matches(request)
     }
+
+    fn name(&self) -> String {
+        self.0.name()
+    }
 }
 
 impl Debug for Matcher {
@@ -214,14 +247,71 @@ impl Debug for Matcher {
 #[derive(Debug)]
 pub struct Mock {
     pub(crate) matchers: Vec<Matcher>,
-    pub(crate) response: ResponseTemplate,
+    pub(crate) response: Responder,
     // Maximum number of times (inclusive) we should return a response from this Mock on
     // matching requests.
     // If `None`, there is no cap and we will respond to all incoming matching requests.
     // If `Some(max_n_matches)`, when `max_n_matches` matching incoming requests have been processed,
     // `self.matches` should start returning `false`, regardless of the incoming request.
     pub(crate) max_n_matches: Option<u64>,
+    // If `Some(n)`, the Mock only responds to the n-th incoming request (1-indexed) that
+    // satisfies all its matchers.
+    pub(crate) only_on_call: Option<u64>,
     pub(crate) expectation: Times,
+    // Human-readable identifier used in diagnostics, see `Mock::named`.
+    pub(crate) name: Option<String>,
+    // Shared buffer the body of each matched request is appended to, see `Mock::capture_body_into`.
+    pub(crate) body_sink: Option<Arc<Mutex<Vec<u8>>>>,
+    // If `Some((start, end))`, the Mock only matches requests received in `[start, end)`.
+    pub(crate) active_window: Option<(Instant, Instant)>,
+    // Invoked with every matched request, see `Mock::on_match`.
+    pub(crate) on_match: Option<MatchCallback>,
+    // Cleared while the Mock is disabled via its `MockGuard`: shared with the guard.
+    pub(crate) enabled: Arc<AtomicBool>,
+}
+
+/// Wrapper around the closure passed to `Mock::on_match`.
+///
+/// Same as `Matcher`: we need a (fake) implementation of `Debug` to pass it around
+/// as part of a `bastion` message.
+pub(crate) struct MatchCallback(pub(crate) Box<dyn Fn(&Request) + Send + Sync>);
+
+impl Debug for MatchCallback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MatchCallback")
+    }
+}
+
+/// A snapshot of a [`Mock`] mounted on a [`MockServer`], returned by [`MockServer::mounted_mocks`].
+///
+/// It is meant for debugging: its `Debug` representation tells you at a glance what
+/// is registered and how many requests it matched so far.
+///
+/// [`Mock`]: struct.Mock.html
+/// [`MockServer`]: struct.MockServer.html
+/// [`MockServer::mounted_mocks`]: struct.MockServer.html#method.mounted_mocks
+#[derive(Debug, Clone)]
+pub struct MockSummary {
+    /// The name assigned via [`Mock::named`], if any.
+    ///
+    /// [`Mock::named`]: struct.Mock.html#method.named
+    pub name: Option<String>,
+    /// The description of each matcher of the [`Mock`], in order - see [`Match::name`].
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`Match::name`]: trait.Match.html#method.name
+    pub matchers: Vec<String>,
+    /// The expected number of matching requests, set via [`Mock::expect`], using Rust's
+    /// range syntax (e.g. `1`, `1..=3` or `..` if there is no expectation).
+    ///
+    /// [`Mock::expect`]: struct.Mock.html#method.expect
+    pub expectation: String,
+    /// Whether the expectation is satisfied by the requests matched so far.
+    pub expectation_satisfied: bool,
+    /// The number of requests the [`Mock`] matched so far.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    pub n_matched_requests: u64,
 }
 
 /// A fluent builder to construct a [`Mock`] instance given matchers and a [`ResponseTemplate`].
@@ -298,6 +388,67 @@ impl Mock {
         self
     }
 
+    /// Only respond to the `n`-th incoming request (1-indexed) that satisfies all the
+    /// [`matchers`] of this `Mock`: all other requests are let through, as if the `Mock`
+    /// was not there.
+    ///
+    /// Mount it before a `Mock` with the same matchers to inject a single failure at a
+    /// precise point of a sequence of identical requests: mocks are evaluated in the order
+    /// they were mounted.
+    ///
+    /// Every request satisfying the matchers counts as an attempt, even if it is not
+    /// responded to - and expectations (see [`expect`]) are only checked against the
+    /// request that actually got a response.  
+    /// A `Mock` with `only_on_call` responds to at most one request: combining it with
+    /// [`up_to_n_times`] has no effect.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///
+    ///     // The second GET request fails...
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(500))
+    ///         .only_on_call(2)
+    ///         .expect(1)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///     // ...all the others succeed.
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut statuses = vec![];
+    ///     for _ in 0..3 {
+    ///         let status = surf::get(&mock_server.uri())
+    ///             .await
+    ///             .unwrap()
+    ///             .status();
+    ///         statuses.push(status.as_u16());
+    ///     }
+    ///
+    ///     // Assert
+    ///     assert_eq!(statuses, vec![200, 500, 200]);
+    /// }
+    /// ```
+    ///
+    /// [`matchers`]: matchers/index.html
+    /// [`expect`]: #method.expect
+    /// [`up_to_n_times`]: #method.up_to_n_times
+    pub fn only_on_call(mut self, n: u64) -> Mock {
+        assert!(n > 0, "n must be strictly greater than 0!");
+        self.only_on_call = Some(n);
+        self
+    }
+
     /// Set an expectation on the number of times this `Mock` should match in the current
     /// test case.
     /// Expectations are verified when the [`MockServer`] is shutting down: if the expectation
@@ -359,6 +510,216 @@ impl Mock {
         self
     }
 
+    /// Assign a name to this `Mock`.
+    ///
+    /// The name has no influence on matching: it is used to identify the `Mock` in
+    /// diagnostics - e.g. the `matched_mock` field of the spans emitted when the `tracing`
+    /// feature is enabled.  
+    /// It comes in handy when many `Mock`s with similar matchers are mounted on the same
+    /// [`MockServer`].
+    ///
+    /// ### Example:
+    ///
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .named("Healthcheck")
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(status.as_u16(), 200);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub fn named<T: Into<String>>(mut self, name: T) -> Mock {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Append the body of every request matched by this `Mock` to `sink`.
+    ///
+    /// Each matched request is captured exactly once, when it matches, before the response
+    /// is generated: after your code has run you can assert on the exact bytes it uploaded
+    /// without going through the [`MockServer`].  
+    /// Bodies of consecutive matched requests are concatenated: `.up_to_n_times(1)` ensures
+    /// the sink only ever holds a single body.  
+    /// Requests failing the matchers of this `Mock` (or matched by a different one) are
+    /// not captured.
+    ///
+    /// Calling it again replaces the previous sink.
+    ///
+    /// ### Example:
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let uploaded = Arc::new(Mutex::new(Vec::new()));
+    ///
+    ///     Mock::given(method("PUT"))
+    ///         .respond_with(ResponseTemplate::new(201))
+    ///         .capture_body_into(uploaded.clone())
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let status = surf::put(&mock_server.uri())
+    ///         .body_bytes(b"\x00\x01\x02".to_vec())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(status.as_u16(), 201);
+    ///     assert_eq!(*uploaded.lock().unwrap(), vec![0u8, 1, 2]);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub fn capture_body_into(mut self, sink: Arc<Mutex<Vec<u8>>>) -> Mock {
+        self.body_sink = Some(sink);
+        self
+    }
+
+    /// Only match requests received by the [`MockServer`] between `start` (inclusive) and
+    /// `end` (exclusive).
+    ///
+    /// Outside of the window the `Mock` is inert: requests are not checked against its matchers
+    /// (they don't count towards [`only_on_call`] nor [`expect`]) and other mounted `Mock`s get
+    /// a chance to handle them.  
+    /// It lets you test a client that changes behaviour over time (e.g. an upstream that
+    /// goes down for a while) without swapping `Mock`s by hand halfway through the test.
+    ///
+    /// It panics if `end` comes before `start`.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let now = Instant::now();
+    ///
+    ///     // An outage, starting in an hour from now
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(503))
+    ///         .active_between(now + Duration::from_secs(3600), now + Duration::from_secs(7200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///
+    ///     // Assert - the outage has not started yet
+    ///     assert_eq!(status.as_u16(), 200);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`only_on_call`]: #method.only_on_call
+    /// [`expect`]: #method.expect
+    pub fn active_between(mut self, start: Instant, end: Instant) -> Mock {
+        assert!(start <= end, "The window must not end before it starts.");
+        self.active_window = Some((start, end));
+        self
+    }
+
+    /// Invoke `f` with every request matched by this `Mock`.
+    ///
+    /// It is a general purpose hook to coordinate your test with the [`MockServer`]
+    /// (e.g. signal a channel when the webhook your code is supposed to send has arrived)
+    /// instead of polling it.
+    ///
+    /// `f` is called once per matched request:
+    /// - after the match has been recorded (i.e. it counts towards [`expect`] and the body
+    ///   has been appended to the sink of [`capture_body_into`], if any);
+    /// - before the response is generated and sent back to the client - including the
+    ///   delay specified via [`ResponseTemplate::set_delay`].
+    ///
+    /// `f` runs on the [`MockServer`] and holds up every other request while it is running:
+    /// keep it short and never block on something that is waiting for a response.  
+    /// If `f` panics the [`MockServer`] stops serving requests: all of them get a `500`.
+    ///
+    /// Calling it again replaces the previous callback.
+    ///
+    /// ### Example:
+    ///
+    /// ```rust
+    /// use std::sync::mpsc;
+    /// use std::sync::Mutex;
+    /// use wiremock::{MockServer, Mock, Request, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let (sender, receiver) = mpsc::channel();
+    ///     let sender = Mutex::new(sender);
+    ///
+    ///     Mock::given(method("POST"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .on_match(move |request: &Request| {
+    ///             sender.lock().unwrap().send(request.body.clone()).unwrap();
+    ///         })
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     surf::post(&mock_server.uri())
+    ///         .body_string("webhook".into())
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(receiver.recv().unwrap(), b"webhook".to_vec());
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`expect`]: #method.expect
+    /// [`capture_body_into`]: #method.capture_body_into
+    /// [`ResponseTemplate::set_delay`]: struct.ResponseTemplate.html#method.set_delay
+    pub fn on_match<F>(mut self, f: F) -> Mock
+    where
+        F: Fn(&Request) + Send + Sync + 'static,
+    {
+        self.on_match = Some(MatchCallback(Box::new(f)));
+        self
+    }
+
     /// Mount a `Mock` on an instance of [`MockServer`].
     ///
     /// Be careful! `Mock`s are not effective until they are [`mount`]ed or [`register`]ed on a [`MockServer`].
@@ -375,17 +736,30 @@ impl Mock {
     /// Build an instance of `http_types::Response` from the [`ResponseTemplate`] associated
     /// with a `Mock`.
     ///
+    /// Responders can pick a different template for each request: the response is the one
+    /// they return for a bare `GET /` request, without headers nor body.
+    ///
     /// [`ResponseTemplate`]: struct.ResponseTemplate.html
     pub fn response(&self) -> Response {
-        self.response.generate_response()
+        let request = Request {
+            url: "http://localhost/".parse().unwrap(),
+            method: Some(Method::Get),
+            raw_method: "GET".to_owned(),
+            raw_target: "/".to_owned(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            received_at: Instant::now(),
+            matched_mock: None,
+        };
+        self.response_template(&request).generate_response(&request)
     }
 
-    /// Build an instance of `http_types::Response` from the [`ResponseTemplate`] associated
-    /// with a `Mock`.
+    /// Determine the [`ResponseTemplate`] that should be used to reply to `request`
+    /// using the responder associated with a `Mock`.
     ///
     /// [`ResponseTemplate`]: struct.ResponseTemplate.html
-    pub(crate) fn delay(&self) -> &Option<Duration> {
-        self.response.delay()
+    pub(crate) fn response_template(&self, request: &Request) -> ResponseTemplate {
+        self.response.respond(request)
     }
 }
 
@@ -404,20 +778,31 @@ impl MockBuilder {
     /// Establish what [`ResponseTemplate`] should be used to generate a response when an incoming
     /// request matches.
     ///
+    /// You can pass a [`ResponseTemplate`] or anything else implementing the [`Respond`] trait,
+    /// e.g. one of the [`responders`] provided out-of-the-box.
+    ///
     /// `respond_with` finalises the `MockBuilder` and returns you a [`Mock`] instance, ready to
     /// be [`register`]ed or [`mount`]ed on a [`MockServer`]!
     ///
     /// [`Mock`]: struct.Mock.html
     /// [`MockServer`]: struct.MockServer.html
+    /// [`Respond`]: trait.Respond.html
     /// [`ResponseTemplate`]: struct.ResponseTemplate.html
+    /// [`responders`]: responders/index.html
     /// [`register`]: struct.MockServer.html#method.register
     /// [`mount`]: #method.mount
-    pub fn respond_with(self, template: ResponseTemplate) -> Mock {
+    pub fn respond_with<R: Respond + 'static>(self, responder: R) -> Mock {
         Mock {
             matchers: self.matchers,
-            response: template,
+            response: Responder(Box::new(responder)),
             max_n_matches: None,
+            only_on_call: None,
             expectation: Times(TimesEnum::Unbounded(RangeFull)),
+            name: None,
+            body_sink: None,
+            active_window: None,
+            on_match: None,
+            enabled: Arc::new(AtomicBool::new(true)),
         }
     }
 }
@@ -452,6 +837,22 @@ impl MockBuilder {
 #[derive(Debug)]
 pub struct Times(TimesEnum);
 
+// The expectation is formatted using Rust's range syntax, e.g. `1..=3` or `2..`.
+// `..` stands for "any number of times".
+impl std::fmt::Display for Times {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            TimesEnum::Exact(e) => write!(f, "{}", e),
+            TimesEnum::Unbounded(_) => write!(f, ".."),
+            TimesEnum::Range(r) => write!(f, "{}..{}", r.start, r.end),
+            TimesEnum::RangeFrom(r) => write!(f, "{}..", r.start),
+            TimesEnum::RangeTo(r) => write!(f, "..{}", r.end),
+            TimesEnum::RangeToInclusive(r) => write!(f, "..={}", r.end),
+            TimesEnum::RangeInclusive(r) => write!(f, "{}..={}", r.start(), r.end()),
+        }
+    }
+}
+
 impl Times {
     pub(crate) fn contains(&self, n_calls: u64) -> bool {
         match &self.0 {
@@ -1,66 +1,263 @@
 use crate::active_mock::ActiveMock;
-use crate::{Mock, Request};
+use crate::content_encoding;
+use crate::instrumentation::RequestSpan;
+use crate::liveness::Liveness;
+use crate::mock::MockSummary;
+use crate::mock_server_builder::{RequestMiddleware, ResponseMiddleware};
+use crate::{Mock, Request, Respond};
 use bastion::prelude::*;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use futures_timer::Delay;
 use http_types::{Response, StatusCode};
 use log::{debug, warn};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
 use std::time::Duration;
 
 #[derive(Clone)]
 pub(crate) struct MockActor {
     pub actor_ref: ChildRef,
+    pub(crate) liveness: Liveness,
 }
 
 #[derive(Clone, Debug)]
 struct Reset {}
 
+#[derive(Clone, Debug)]
+struct ResetCounters {}
+
 #[derive(Clone, Debug)]
 struct Verify {}
 
+#[derive(Clone, Debug)]
+struct RequestCounts {}
+
+// Register `mock`, keeping track of the requests it matches if it is `scoped` (see
+// `MockGuard::received_requests`).
+#[derive(Debug)]
+struct Register {
+    mock: Mock,
+    scoped: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Deregister {
+    mock_id: u64,
+}
+
+#[derive(Clone, Debug)]
+struct ReceivedRequests {
+    mock_id: u64,
+}
+
+#[derive(Clone, Debug)]
+struct MountedMocks {}
+
+// Ask for the number of requests that did not match any mock, along with the requests
+// themselves (if recording is enabled).
+#[derive(Clone, Debug)]
+struct UnmatchedRequests {}
+
+// Ask for every request received from now on to be sent down `sender`.
+#[derive(Clone, Debug)]
+struct Subscribe {
+    sender: UnboundedSender<Request>,
+}
+
+// A request to respond to, along with the span it is being handled in.
+#[derive(Clone, Debug)]
+pub(crate) struct IncomingRequest {
+    pub(crate) request: Request,
+    pub(crate) span: RequestSpan,
+}
+
+// Ask for all the requests received by the server, clearing the log if `drain` is `true`.
+#[derive(Clone, Debug)]
+struct RecordedRequests {
+    drain: bool,
+}
+
 impl MockActor {
     /// Start an instance of our MockActor and return a reference to it.
-    pub(crate) fn start() -> MockActor {
+    ///
+    /// If `rng_seed` is specified, it is used to derive a seed for the responder of each
+    /// registered mock.  
+    /// If `decompress_request_bodies` is `true`, request bodies are decoded according to their
+    /// `Content-Encoding` header before being matched.  
+    /// If `record_requests` is `true`, every incoming request is kept in a log, regardless of
+    /// it matching a mock or not.  
+    /// If `not_found_body` is specified, it is used as the body of the 404 returned for
+    /// requests that did not match any mock.  
+    /// `request_middlewares` are applied, in order, to every request before it is recorded
+    /// and matched, `response_middlewares` to every response - matched or not.
+    pub(crate) fn start(
+        rng_seed: Option<u64>,
+        decompress_request_bodies: bool,
+        record_requests: bool,
+        not_found_body: Option<Vec<u8>>,
+        request_middlewares: Vec<RequestMiddleware>,
+        response_middlewares: Vec<ResponseMiddleware>,
+    ) -> MockActor {
+        let liveness = Liveness::new();
+        let actor_liveness = liveness.clone();
         let mock_actors = Bastion::children(|children: Children| {
-            children.with_exec(move |ctx: BastionContext| async move {
+            children.with_exec(move |ctx: BastionContext| {
+                let liveness_guard = actor_liveness.guard();
+                let not_found_body = not_found_body.clone();
+                let request_middlewares = request_middlewares.clone();
+                let response_middlewares = response_middlewares.clone();
+                async move {
+                // Dropped if the actor panics (e.g. in a custom matcher or responder).
+                let _liveness_guard = liveness_guard;
                 let mut mocks: Vec<ActiveMock> = vec![];
+                let mut rng = rng_seed.map(StdRng::seed_from_u64);
+                // Number of requests received by the server and number of requests
+                // that did not match any of the mounted mocks.
+                let mut n_requests: u64 = 0;
+                let mut n_unmatched_requests: u64 = 0;
+                // All requests received by the server, in order, if `record_requests` is `true`.
+                let mut recorded_requests: Vec<Request> = vec![];
+                // The subset of `recorded_requests` that did not match any mock.
+                let mut recorded_unmatched_requests: Vec<Request> = vec![];
+                // Identifier assigned to the next registered mock.
+                let mut next_mock_id: u64 = 0;
+                // Consumers of `MockServer::request_stream`, dropped as soon as they hang up.
+                let mut subscribers: Vec<UnboundedSender<Request>> = vec![];
                 loop {
                     msg! { ctx.recv().await?,
                         _reset: Reset =!> {
                             debug!("Dropping all mocks.");
                             mocks = vec![];
+                            n_requests = 0;
+                            n_unmatched_requests = 0;
+                            recorded_requests.clear();
+                            recorded_unmatched_requests.clear();
                             answer!(ctx, "Reset.").unwrap();
                         };
+                        _reset_counters: ResetCounters =!> {
+                            debug!("Resetting request counters of all mocks.");
+                            for mock in &mut mocks {
+                                mock.reset_counters();
+                            }
+                            n_requests = 0;
+                            n_unmatched_requests = 0;
+                            recorded_requests.clear();
+                            recorded_unmatched_requests.clear();
+                            answer!(ctx, "Reset counters.").unwrap();
+                        };
+                        subscribe: Subscribe =!> {
+                            subscribers.push(subscribe.sender);
+                            answer!(ctx, "Subscribed.").unwrap();
+                        };
+                        _request_counts: RequestCounts =!> {
+                            answer!(ctx, (n_requests, n_unmatched_requests)).unwrap();
+                        };
+                        deregister: Deregister =!> {
+                            debug!("Dropping mock {}.", deregister.mock_id);
+                            // A mock that is no longer there (e.g. after a reset) has nothing
+                            // left to verify.
+                            let mut verified = true;
+                            if let Some(index) = mocks.iter().position(|m| m.id() == deregister.mock_id) {
+                                verified = mocks.remove(index).verify();
+                            }
+                            answer!(ctx, verified).unwrap();
+                        };
+                        received_requests: ReceivedRequests =!> {
+                            let requests: Vec<Request> = mocks
+                                .iter()
+                                .find(|m| m.id() == received_requests.mock_id)
+                                .map(|m| m.received_requests().to_vec())
+                                .unwrap_or_default();
+                            answer!(ctx, requests).unwrap();
+                        };
+                        recorded: RecordedRequests =!> {
+                            let requests: Option<Vec<Request>> = if !record_requests {
+                                None
+                            } else if recorded.drain {
+                                Some(std::mem::take(&mut recorded_requests))
+                            } else {
+                                Some(recorded_requests.clone())
+                            };
+                            answer!(ctx, requests).unwrap();
+                        };
+                        _unmatched: UnmatchedRequests =!> {
+                            answer!(ctx, (n_unmatched_requests, recorded_unmatched_requests.clone())).unwrap();
+                        };
+                        _mounted_mocks: MountedMocks =!> {
+                            let summaries: Vec<MockSummary> = mocks.iter().map(|m| m.summary()).collect();
+                            answer!(ctx, summaries).unwrap();
+                        };
                         _verify: Verify =!> {
                             debug!("Verifying expectations for all mounted mocks.");
                             let verified = mocks.iter().all(|m| m.verify());
                             answer!(ctx, verified).unwrap();
                         };
-                        mock: Mock =!> {
+                        register: Register =!> {
                             debug!("Registering mock.");
-                            mocks.push(ActiveMock::new(mock));
-                            answer!(ctx, "Registered.").unwrap();
+                            let Register { mut mock, scoped } = register;
+                            if let Some(rng) = rng.as_mut() {
+                                mock.response.reseed(rng.next_u64());
+                            }
+                            let mock_id = next_mock_id;
+                            next_mock_id += 1;
+                            mocks.push(ActiveMock::new(mock_id, mock, scoped && record_requests));
+                            answer!(ctx, mock_id).unwrap();
                         };
-                        request: http_types::Request =!> {
+                        incoming: IncomingRequest =!> {
                             debug!("Handling request.");
-                            let request = Request::from(request).await;
+                            let IncomingRequest { mut request, span } = incoming;
+                            if decompress_request_bodies {
+                                content_encoding::decode_body(&mut request);
+                            }
+                            let request = request_middlewares
+                                .iter()
+                                .fold(request, |request, middleware| (middleware.0)(request));
+                            n_requests += 1;
 
                             let mut response: Option<Response> = None;
                             let mut delay: Option<Duration> = None;
-                            for mock in &mut mocks {
-                                if mock.matches(&request) {
-                                    response = Some(mock.response());
-                                    delay = mock.delay().to_owned();
-                                    break;
-                                }
+                            let matched = span.matching(|| {
+                                mocks.iter_mut().position(|mock| mock.matches(&request))
+                            });
+                            let mut request = request;
+                            request.matched_mock = matched.map(|index| mocks[index].label());
+                            if record_requests {
+                                recorded_requests.push(request.clone());
+                            }
+                            subscribers.retain(|subscriber| {
+                                subscriber.unbounded_send(request.clone()).is_ok()
+                            });
+                            if let Some(mock) = matched.map(|index| &mocks[index]) {
+                                debug!("Request matched by {}.", mock.label());
+                                span.record_match(mock);
+                                let (generated, template_delay) = span.responding(|| {
+                                    let template = mock.response_template(&request);
+                                    (template.generate_response(&request), template.delay().to_owned())
+                                });
+                                response = Some(generated);
+                                delay = template_delay;
                             }
                             if let Some(response) = response {
+                                let response = response_middlewares
+                                    .iter()
+                                    .fold(response, |response, middleware| (middleware.0)(&request, response));
                                 if let Some(delay) = delay {
                                     Delay::new(delay).await;
                                 }
                                 answer!(ctx, response).unwrap();
                             } else {
                                 debug!("Got unexpected request:\n{}", request);
-                                let res = Response::new(StatusCode::NotFound);
+                                n_unmatched_requests += 1;
+                                if record_requests {
+                                    recorded_unmatched_requests.push(request.clone());
+                                }
+                                let mut res = Response::new(StatusCode::NotFound);
+                                if let Some(body) = &not_found_body {
+                                    res.set_body(body.clone());
+                                }
+                                let res = response_middlewares
+                                    .iter()
+                                    .fold(res, |response, middleware| (middleware.0)(&request, response));
                                 answer!(ctx, res).unwrap();
                             }
                         };
@@ -69,6 +266,7 @@ impl MockActor {
                         };
                     }
                 }
+                }
             })
         })
         .expect("Couldn't create the mock actor.");
@@ -76,11 +274,100 @@ impl MockActor {
         let mock_actor = mock_actors.elems()[0].clone();
         MockActor {
             actor_ref: mock_actor,
+            liveness,
         }
     }
 
-    pub(crate) async fn register(&self, mock: Mock) {
-        self.actor_ref.ask_anonymously(mock).unwrap().await.unwrap();
+    /// Register a mock, returning the identifier it has been assigned.
+    ///
+    /// The requests matched by `scoped` mocks are recorded, unless recording is disabled.
+    pub(crate) async fn register(&self, mock: Mock, scoped: bool) -> u64 {
+        let answer = self
+            .actor_ref
+            .ask_anonymously(Register { mock, scoped })
+            .unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            mock_id: u64 => mock_id;
+            _: _ => panic!("Failed to register the mock.");
+        };
+        response
+    }
+
+    /// Drop the mock with the specified identifier, returning `true` if its expectations
+    /// were satisfied.
+    pub(crate) async fn deregister(&self, mock_id: u64) -> bool {
+        let answer = self
+            .actor_ref
+            .ask_anonymously(Deregister { mock_id })
+            .unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            outcome: bool => outcome;
+            _: _ => false;
+        };
+        response
+    }
+
+    /// Return the requests matched so far by the mock with the specified identifier.
+    pub(crate) async fn received_requests(&self, mock_id: u64) -> Vec<Request> {
+        let answer = self
+            .actor_ref
+            .ask_anonymously(ReceivedRequests { mock_id })
+            .unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            requests: Vec<Request> => requests;
+            _: _ => vec![];
+        };
+        response
+    }
+
+    /// Return all the requests received by the server, or `None` if recording is disabled.
+    ///
+    /// If `drain` is `true`, the log is cleared.
+    pub(crate) async fn recorded_requests(&self, drain: bool) -> Option<Vec<Request>> {
+        let answer = self
+            .actor_ref
+            .ask_anonymously(RecordedRequests { drain })
+            .unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            requests: Option<Vec<Request>> => requests;
+            _: _ => None;
+        };
+        response
+    }
+
+    /// Return the number of requests that did not match any mock and, if recording is
+    /// enabled, the requests themselves.
+    pub(crate) async fn unmatched_requests(&self) -> (u64, Vec<Request>) {
+        let answer = self
+            .actor_ref
+            .ask_anonymously(UnmatchedRequests {})
+            .unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            unmatched: (u64, Vec<Request>) => unmatched;
+            _: _ => (0, vec![]);
+        };
+        response
+    }
+
+    /// Return a stream yielding every request received by the server from now on.
+    pub(crate) async fn subscribe(&self) -> UnboundedReceiver<Request> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.actor_ref
+            .ask_anonymously(Subscribe { sender })
+            .unwrap()
+            .await
+            .unwrap();
+        receiver
+    }
+
+    /// Return a snapshot of all the mounted mocks, in the order they were registered.
+    pub(crate) async fn mounted_mocks(&self) -> Vec<MockSummary> {
+        let answer = self.actor_ref.ask_anonymously(MountedMocks {}).unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            summaries: Vec<MockSummary> => summaries;
+            _: _ => vec![];
+        };
+        response
     }
 
     pub(crate) async fn reset(&self) {
@@ -91,6 +378,25 @@ impl MockActor {
             .unwrap();
     }
 
+    pub(crate) async fn reset_counters(&self) {
+        self.actor_ref
+            .ask_anonymously(ResetCounters {})
+            .unwrap()
+            .await
+            .unwrap();
+    }
+
+    /// Return the number of requests received by the server and the number of requests
+    /// that did not match any mock, in this order.
+    pub(crate) async fn request_counts(&self) -> (u64, u64) {
+        let answer = self.actor_ref.ask_anonymously(RequestCounts {}).unwrap();
+        let response = msg! { answer.await.expect("Couldn't receive the answer."),
+            counts: (u64, u64) => counts;
+            _: _ => (0, 0);
+        };
+        response
+    }
+
     pub(crate) async fn verify(&self) -> bool {
         let answer = self.actor_ref.ask_anonymously(Verify {}).unwrap();
         let response = msg! { answer.await.expect("Couldn't receive the answer."),
@@ -0,0 +1,168 @@
+use crate::mock_actor::MockActor;
+use crate::Request;
+use bastion::run;
+use log::debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A handle to a [`Mock`] registered on a [`MockServer`] using [`register_scoped`].
+///
+/// The [`Mock`] stays active as long as its `MockGuard` is alive: when the guard goes out
+/// of scope, the expectations of the [`Mock`] are verified (panicking if they are not
+/// satisfied) and the [`Mock`] is removed from the [`MockServer`].
+///
+/// It lets you scope a [`Mock`] to a portion of a test, inspect the requests it matched and
+/// temporarily disable it (see [`disable`]).
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::method;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     {
+///         let mock = Mock::given(method("POST"))
+///             .respond_with(ResponseTemplate::new(200))
+///             .expect(1);
+///         let guard = mock_server.register_scoped(mock).await;
+///
+///         // Act
+///         let status = surf::post(&mock_server.uri())
+///             .body_string("hello".into())
+///             .await
+///             .unwrap()
+///             .status();
+///
+///         // Assert
+///         assert_eq!(status.as_u16(), 200);
+///         let received_requests = guard.received_requests().await;
+///         assert_eq!(received_requests.len(), 1);
+///         assert_eq!(received_requests[0].body, b"hello");
+///
+///         // Expectations are verified here, when `guard` is dropped.
+///     }
+///
+///     // The mock is gone!
+///     let status = surf::post(&mock_server.uri())
+///         .await
+///         .unwrap()
+///         .status();
+///     assert_eq!(status.as_u16(), 404);
+/// }
+/// ```
+///
+/// [`Mock`]: struct.Mock.html
+/// [`MockServer`]: struct.MockServer.html
+/// [`register_scoped`]: struct.MockServer.html#method.register_scoped
+/// [`disable`]: #method.disable
+#[must_use = "The mock is dropped from the server as soon as its guard goes out of scope"]
+pub struct MockGuard {
+    pub(crate) mock_actor: MockActor,
+    pub(crate) mock_id: u64,
+    pub(crate) enabled: Arc<AtomicBool>,
+}
+
+impl MockGuard {
+    /// Return the requests matched by this [`Mock`] so far, in the order they were received.
+    ///
+    /// Unlike a global log of received requests, you do not have to figure out which of them
+    /// were handled by this specific [`Mock`].  
+    /// It is always empty if request recording has been disabled (see
+    /// [`MockServerBuilder::disable_request_recording`]).
+    ///
+    /// `received_requests` is an asynchronous method, make sure to `.await` it!
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`MockServerBuilder::disable_request_recording`]: struct.MockServerBuilder.html#method.disable_request_recording
+    pub async fn received_requests(&self) -> Vec<Request> {
+        self.mock_actor.received_requests(self.mock_id).await
+    }
+
+    /// Temporarily disable this [`Mock`]: until [`enable`] is called, the [`MockServer`]
+    /// skips it as if it was not mounted.
+    ///
+    /// It lets you simulate an endpoint going away and coming back within the same test,
+    /// without losing track of what the [`Mock`] matched so far.  
+    /// While disabled, the [`Mock`] does not look at incoming requests at all: they do not
+    /// count towards its expectations (see [`Mock::expect`]), [`up_to_n_times`] or
+    /// [`only_on_call`] and they are not added to its [`received_requests`].  
+    /// Counters are not reset either: they pick up where they left off once the [`Mock`] is
+    /// enabled again.  
+    /// Expectations are still verified when the guard is dropped, even if the [`Mock`] is
+    /// disabled at that point.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let mock = Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .expect(2);
+    ///     let guard = mock_server.register_scoped(mock).await;
+    ///
+    ///     // Act
+    ///     let before = surf::get(&mock_server.uri()).await.unwrap().status();
+    ///     guard.disable();
+    ///     let during = surf::get(&mock_server.uri()).await.unwrap().status();
+    ///     guard.enable();
+    ///     let after = surf::get(&mock_server.uri()).await.unwrap().status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(before.as_u16(), 200);
+    ///     assert_eq!(during.as_u16(), 404);
+    ///     assert_eq!(after.as_u16(), 200);
+    /// }
+    /// ```
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`enable`]: #method.enable
+    /// [`Mock::expect`]: struct.Mock.html#method.expect
+    /// [`up_to_n_times`]: struct.Mock.html#method.up_to_n_times
+    /// [`only_on_call`]: struct.Mock.html#method.only_on_call
+    /// [`received_requests`]: #method.received_requests
+    pub fn disable(&self) {
+        self.enabled.store(false, Ordering::SeqCst);
+    }
+
+    /// Enable this [`Mock`] again after a call to [`disable`].
+    ///
+    /// `Mock`s are enabled when they are registered: calling `enable` on an enabled
+    /// [`Mock`] has no effect.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`disable`]: #method.disable
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether this [`Mock`] is currently enabled - see [`disable`].
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`disable`]: #method.disable
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        debug!("Verify scoped mock expectations.");
+        if !run!(self.mock_actor.deregister(self.mock_id)) {
+            if std::thread::panicking() {
+                debug!("Verification failed: scoped mock expectations have not been satisfied.");
+            } else {
+                panic!("Verification failed: scoped mock expectations have not been satisfied.");
+            }
+        }
+    }
+}
@@ -1,12 +1,26 @@
-use crate::mock::Mock;
+use crate::error::Error;
+use crate::matchers::path;
+use crate::mock::{Mock, MockSummary};
 use crate::mock_actor::MockActor;
-use crate::server_actor::ServerActor;
+use crate::mock_guard::MockGuard;
+use crate::mock_server_builder::MockServerBuilder;
+use crate::server_actor::{get_available_port, ServerActor};
+use crate::test_scope;
+use crate::{Request, ResponseTemplate};
 use async_std::net::TcpStream;
 use bastion::{run, Bastion};
-use log::debug;
+use futures::Stream;
+use http_types::{StatusCode, Url};
+use log::{debug, warn};
+use std::convert::TryInto;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
+// How many unmatched requests, at most, are listed by `MockServer::report`.
+const REPORTED_UNMATCHED_REQUESTS: usize = 10;
+
 /// An HTTP web-server running in the background to behave as one of your dependencies using `Mock`s for testing purposes.
 ///
 /// Each instance of `MockServer` is fully isolated: `start` takes care of finding a random port
@@ -18,12 +32,73 @@ use std::time::Duration;
 /// with and needs mocking for testing purposes.
 ///
 /// You should use one instance of `MockServer` for each test, to ensure full isolation and
-/// no cross-test interference.
+/// no cross-test interference.  
+/// If starting a `MockServer` for each test is too slow for your test suite, you can share
+/// one between many tests with [`LazyMockServer`] - at the cost of some discipline.
 ///
 /// You can register as many `Mock`s as your scenario requires on a `MockServer`.
+///
+/// [`LazyMockServer`]: struct.LazyMockServer.html
 pub struct MockServer {
     server_actor: ServerActor,
     mock_actor: MockActor,
+    // Set once the `MockServer` has been verified and its actors killed - either when it is
+    // dropped or when the `TestScope` it was started in ends.
+    shut_down: Arc<AtomicBool>,
+}
+
+/// A handle to verify and shut down a `MockServer` without owning it, see `TestScope`.
+#[derive(Clone)]
+pub(crate) struct ServerHandle {
+    server_actor: ServerActor,
+    mock_actor: MockActor,
+    shut_down: Arc<AtomicBool>,
+}
+
+impl ServerHandle {
+    /// Verify the expectations of the mounted mocks and kill the actors backing the
+    /// `MockServer`, unless it has already been done.
+    ///
+    /// It returns an error, describing why, if verification failed: it's up to the caller
+    /// to panic.  
+    /// A mock actor that died (e.g. a matcher panicked) fails verification: the state of the
+    /// mocks is gone, we cannot tell if their expectations have been satisfied.
+    pub(crate) fn shut_down(&self) -> Result<(), &'static str> {
+        if self.shut_down.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        debug!("Verify mock expectations.");
+        let verified = if !self.mock_actor.liveness.is_alive() {
+            warn!("The mock server stopped running: mock expectations cannot be verified.");
+            Err("the mock server died, mock expectations cannot be verified.")
+        } else if run!(self.mock_actor.verify()) {
+            Ok(())
+        } else {
+            Err("mock expectations have not been satisfied.")
+        };
+        debug!("Killing server actor.");
+        self.server_actor.actor_ref.kill().unwrap();
+        debug!("Killed server actor.");
+        debug!("Killing mock actor.");
+        // The mock actor might be dead already, see `is_running`.
+        let _ = self.mock_actor.actor_ref.kill();
+        debug!("Killed mock actor.");
+        verified
+    }
+}
+
+/// Panic, unless we are panicking already, if verification failed (see
+/// `ServerHandle::shut_down`).
+pub(crate) fn check_verification(verified: Result<(), &'static str>) {
+    let reason = match verified {
+        Ok(()) => return,
+        Err(reason) => reason,
+    };
+    if std::thread::panicking() {
+        debug!("Verification failed: {}", reason);
+    } else {
+        panic!("Verification failed: {}", reason);
+    }
 }
 
 impl MockServer {
@@ -70,20 +145,99 @@ impl MockServer {
     ///     assert_eq!(status.as_u16(), 404);
     /// }
     /// ```
+    ///
+    /// It panics if the `MockServer` cannot be started - use [`try_start`](#method.try_start)
+    /// if you'd rather get an error back.
     pub async fn start() -> Self {
+        Self::builder().start().await
+    }
+
+    /// Configure a new instance of `MockServer` before starting it.
+    ///
+    /// It returns a [`MockServerBuilder`]: check its documentation for the available options.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let mock_server = MockServer::builder().rng_seed(42).start().await;
+    ///
+    ///     // No mocks mounted - default response
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///     assert_eq!(status.as_u16(), 404);
+    /// }
+    /// ```
+    ///
+    /// [`MockServerBuilder`]: struct.MockServerBuilder.html
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    /// Fallible version of [`start`](#method.start): it returns an [`Error`] instead of
+    /// panicking if the `MockServer` cannot be started (e.g. there is no port available).
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let mock_server = MockServer::try_start()
+    ///         .await
+    ///         .expect("Failed to start the mock server");
+    ///
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///     assert_eq!(status.as_u16(), 404);
+    /// }
+    /// ```
+    ///
+    /// [`Error`]: enum.Error.html
+    pub async fn try_start() -> Result<Self, Error> {
+        Self::builder().try_start().await
+    }
+
+    pub(crate) async fn start_with(builder: MockServerBuilder) -> Result<Self, Error> {
+        // Allocate a random port before spinning up any actor: there is nothing to clean up
+        // if we fail to find one.
+        let listener = get_available_port(builder.listen_backlog).await?;
+
         // Should I put this behind a lazy_static to call them only once?
         Bastion::init();
         Bastion::start();
 
-        let mock_actor = MockActor::start();
+        let mock_actor = MockActor::start(
+            builder.rng_seed,
+            builder.decompress_request_bodies,
+            !builder.disable_request_recording,
+            builder.not_found_body,
+            builder.request_middlewares,
+            builder.response_middlewares,
+        );
 
         // Start our mock server
-        let server_actor = ServerActor::start(mock_actor.clone()).await;
+        let server_actor = ServerActor::start_with(
+            listener,
+            mock_actor.clone(),
+            builder.accept_concurrency,
+            builder.connection_settings,
+        );
 
         let mock_server = Self {
             server_actor,
             mock_actor,
+            shut_down: Arc::new(AtomicBool::new(false)),
         };
+        if !builder.skip_test_scope {
+            test_scope::register(mock_server.handle());
+        }
 
         // Wait (up to 2 second) for the actor to start listening on the specified socket
         for _ in 0..40 {
@@ -94,7 +248,36 @@ impl MockServer {
             async_std::task::sleep(Duration::from_millis(50)).await;
         }
 
-        mock_server
+        Ok(mock_server)
+    }
+
+    /// Check that the background tasks of this `MockServer` are still running.
+    ///
+    /// It returns `false` if the loop accepting incoming connections has stopped or if the
+    /// task matching requests against the mounted `Mock`s has died (e.g. because a custom
+    /// matcher or responder panicked).  
+    /// Requests received while the `MockServer` is in this state get back a
+    /// `500 Internal Server Error`.
+    ///
+    /// Mock expectations cannot be verified once the task matching requests has died: dropping
+    /// the `MockServer` panics, as if they had not been satisfied.
+    ///
+    /// It is a cheap check: use it to get a clear failure in long-running tests instead
+    /// of a puzzling one.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let mock_server = MockServer::start().await;
+    ///
+    ///     assert!(mock_server.is_running());
+    /// }
+    /// ```
+    pub fn is_running(&self) -> bool {
+        self.server_actor.liveness.is_alive() && self.mock_actor.liveness.is_alive()
     }
 
     /// Register a `Mock` on an instance of `MockServer`.
@@ -139,11 +322,117 @@ impl MockServer {
     /// }
     /// ```
     pub async fn register(&self, mock: Mock) {
-        self.mock_actor.register(mock).await;
+        self.mock_actor.register(mock, false).await;
+    }
+
+    /// Register a `Mock` on an instance of `MockServer`, scoping it to the lifetime of the
+    /// returned [`MockGuard`].
+    ///
+    /// When the [`MockGuard`] is dropped, the expectations of the `Mock` are verified and the
+    /// `Mock` is removed from the `MockServer`.  
+    /// In the meantime, you can use the [`MockGuard`] to retrieve the requests the `Mock`
+    /// matched on.
+    ///
+    /// `register_scoped` is an asynchronous method, make sure to `.await` it!
+    ///
+    /// Check [`MockGuard`]'s documentation for an example.
+    ///
+    /// [`MockGuard`]: struct.MockGuard.html
+    pub async fn register_scoped(&self, mock: Mock) -> MockGuard {
+        let enabled = mock.enabled.clone();
+        let mock_id = self.mock_actor.register(mock, true).await;
+        MockGuard {
+            mock_actor: self.mock_actor.clone(),
+            mock_id,
+            enabled,
+        }
+    }
+
+    /// Mount a chain of redirects on an instance of `MockServer`: a request to `paths[0]` is
+    /// redirected to `paths[1]`, `paths[1]` to `paths[2]` and so on, while the last path
+    /// responds with `final_response`.
+    ///
+    /// Each hop is a `302 Found` with a `Location` header holding the absolute uri of the next
+    /// path - use [`mount_redirect_chain_with_status`] to pick a different status code.  
+    /// A chain of `n` paths takes `n - 1` redirects to follow: use it to test that your client
+    /// gives up on (or follows) a given number of redirects.
+    ///
+    /// It panics if `paths` is empty.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, ResponseTemplate};
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     mock_server
+    ///         .mount_redirect_chain(&["/0", "/1", "/2"], ResponseTemplate::new(200))
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let response = surf::get(format!("{}/0", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.status().as_u16(), 302);
+    ///     let next = format!("{}/1", &mock_server.uri());
+    ///     assert_eq!(response.header("location"), Some(next.as_str()));
+    /// }
+    /// ```
+    ///
+    /// [`mount_redirect_chain_with_status`]: #method.mount_redirect_chain_with_status
+    pub async fn mount_redirect_chain(&self, paths: &[&str], final_response: ResponseTemplate) {
+        self.mount_redirect_chain_with_status(paths, StatusCode::Found, final_response)
+            .await;
+    }
+
+    /// Same as [`mount_redirect_chain`], using `status` for each redirect (e.g. `301` or `307`).
+    ///
+    /// It panics if `paths` is empty or if `status` is not a redirection status code (`3xx`).
+    ///
+    /// [`mount_redirect_chain`]: #method.mount_redirect_chain
+    pub async fn mount_redirect_chain_with_status<S>(
+        &self,
+        paths: &[&str],
+        status: S,
+        final_response: ResponseTemplate,
+    ) where
+        S: TryInto<StatusCode>,
+        <S as TryInto<StatusCode>>::Error: std::fmt::Debug,
+    {
+        assert!(
+            !paths.is_empty(),
+            "A redirect chain needs at least one path."
+        );
+        let status: StatusCode = status
+            .try_into()
+            .expect("Failed to convert into status code.");
+        assert!(
+            status.is_redirection(),
+            "{} is not a redirection status code.",
+            status
+        );
+        for hop in paths.windows(2) {
+            let location = self.uri_for(hop[1]);
+            let redirect =
+                ResponseTemplate::new(status).insert_header("location", location.as_str());
+            Mock::given(path(hop[0]))
+                .respond_with(redirect)
+                .mount(self)
+                .await;
+        }
+        Mock::given(path(paths[paths.len() - 1]))
+            .respond_with(final_response)
+            .mount(self)
+            .await;
     }
 
     /// Drop all mounted `Mock`s from an instance of `MockServer`.
     ///
+    /// It also sets back to zero the request counts returned by [`total_requests`] and
+    /// [`unmatched_requests`] and it clears the log of [`received_requests`].
+    ///
     /// ### Example:
     /// ```rust
     /// use wiremock::{MockServer, Mock, ResponseTemplate};
@@ -175,14 +464,438 @@ impl MockServer {
     ///     assert_eq!(status.as_u16(), 404);
     /// }
     /// ```
+    ///
+    /// [`total_requests`]: #method.total_requests
+    /// [`unmatched_requests`]: #method.unmatched_requests
+    /// [`received_requests`]: #method.received_requests
     pub async fn reset(&self) {
         self.mock_actor.reset().await;
     }
 
-    /// Verify that all mounted `Mock`s on this instance of `MockServer` have satisfied
-    /// their expectations on their number of invocations.
-    async fn verify(&self) -> bool {
-        self.mock_actor.verify().await
+    /// Set back to zero all request counters, while keeping the mounted `Mock`s in place.
+    ///
+    /// It resets:
+    /// - the number of requests matched by each `Mock`, used to verify expectations and to
+    ///   enforce [`up_to_n_times`] and [`only_on_call`];
+    /// - the requests recorded for each scoped `Mock` (see [`MockGuard::received_requests`])
+    ///   and by the `MockServer` itself (see [`received_requests`]);
+    /// - the request counts returned by [`total_requests`] and [`unmatched_requests`].
+    ///
+    /// It is a lighter alternative to [`reset`] when running the same scenario in a loop.  
+    /// Requests are processed one at a time against the mounted `Mock`s: a request received
+    /// concurrently with `reset_counters` is either counted before the reset or after it,
+    /// never lost halfway.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .up_to_n_times(1)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     for _ in 0..3 {
+    ///         // Act
+    ///         let status = surf::get(&mock_server.uri())
+    ///             .await
+    ///             .unwrap()
+    ///             .status();
+    ///
+    ///         // Assert
+    ///         assert_eq!(status.as_u16(), 200);
+    ///         assert_eq!(mock_server.total_requests().await, 1);
+    ///
+    ///         mock_server.reset_counters().await;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`up_to_n_times`]: struct.Mock.html#method.up_to_n_times
+    /// [`only_on_call`]: struct.Mock.html#method.only_on_call
+    /// [`MockGuard::received_requests`]: struct.MockGuard.html#method.received_requests
+    /// [`received_requests`]: #method.received_requests
+    /// [`total_requests`]: #method.total_requests
+    /// [`unmatched_requests`]: #method.unmatched_requests
+    /// [`reset`]: #method.reset
+    pub async fn reset_counters(&self) {
+        self.mock_actor.reset_counters().await;
+    }
+
+    /// Return the number of requests received by this instance of `MockServer`, regardless
+    /// of them matching a mounted `Mock` or not.
+    ///
+    /// Use it to verify that your client did not perform more calls than expected - or
+    /// no calls at all.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::path;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(path("/hello"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     surf::get(format!("{}/hello", &mock_server.uri())).await.unwrap();
+    ///     surf::get(format!("{}/missing", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(mock_server.total_requests().await, 2);
+    ///     assert_eq!(mock_server.unmatched_requests().await, 1);
+    /// }
+    /// ```
+    pub async fn total_requests(&self) -> u64 {
+        self.mock_actor.request_counts().await.0
+    }
+
+    /// Return the number of requests received by this instance of `MockServer` that
+    /// did not match any of the mounted `Mock`s (i.e. they got back a 404).
+    ///
+    /// Check [`total_requests`] for an example, and [`verify_no_unmatched`] to assert that
+    /// there are none.
+    ///
+    /// [`total_requests`]: #method.total_requests
+    /// [`verify_no_unmatched`]: #method.verify_no_unmatched
+    pub async fn unmatched_requests(&self) -> u64 {
+        self.mock_actor.request_counts().await.1
+    }
+
+    /// Panic if any request received by this instance of `MockServer` did not match any of
+    /// the mounted `Mock`s, listing the offending requests.
+    ///
+    /// Expectations (see [`Mock::expect`]) prove that your client made the calls you mocked:
+    /// `verify_no_unmatched` proves that it made **only** those calls - use it in strict
+    /// contract tests.  
+    /// Requests are listed only if request recording is enabled (see
+    /// [`MockServerBuilder::disable_request_recording`]); otherwise the panic message just
+    /// mentions how many there were.  
+    /// Like other request counts, unmatched requests are forgotten by [`reset`] and
+    /// [`reset_counters`].
+    ///
+    /// ### Example:
+    /// ```rust,should_panic
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::path;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(path("/hello"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     surf::get(format!("{}/hello", &mock_server.uri())).await.unwrap();
+    ///     surf::get(format!("{}/tracking", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert - panics, listing `GET /tracking`
+    ///     mock_server.verify_no_unmatched().await;
+    /// }
+    /// ```
+    ///
+    /// [`Mock::expect`]: struct.Mock.html#method.expect
+    /// [`MockServerBuilder::disable_request_recording`]: struct.MockServerBuilder.html#method.disable_request_recording
+    /// [`reset`]: #method.reset
+    /// [`reset_counters`]: #method.reset_counters
+    pub async fn verify_no_unmatched(&self) {
+        let (n_unmatched, requests) = self.mock_actor.unmatched_requests().await;
+        if n_unmatched == 0 {
+            return;
+        }
+        let mut message = format!(
+            "Verification failed: {} request(s) did not match any mock.",
+            n_unmatched
+        );
+        if requests.is_empty() {
+            message.push_str(" Request recording is disabled: they cannot be listed.");
+        }
+        for (i, request) in requests.iter().enumerate() {
+            message.push_str(&format!("\n\n{}. {}", i + 1, request));
+        }
+        panic!("{}", message);
+    }
+
+    /// Return a snapshot of the `Mock`s currently mounted on this instance of `MockServer`,
+    /// in the order they were mounted: what they match, what they expect and how many requests
+    /// they matched so far.
+    ///
+    /// Scoped `Mock`s (see [`register_scoped`]) are listed as long as their [`MockGuard`]
+    /// is alive.  
+    /// It is meant for debugging - e.g. to figure out what is registered when mocks are
+    /// mounted dynamically by shared fixtures.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::{method, path};
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(method("GET"))
+    ///         .and(path("/hello"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .named("Hello")
+    ///         .expect(1..)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     surf::get(format!("{}/hello", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert
+    ///     let mocks = mock_server.mounted_mocks().await;
+    ///     assert_eq!(mocks.len(), 1);
+    ///     assert_eq!(mocks[0].name.as_deref(), Some("Hello"));
+    ///     assert_eq!(mocks[0].matchers.len(), 2);
+    ///     assert_eq!(mocks[0].expectation, "1..");
+    ///     assert_eq!(mocks[0].n_matched_requests, 1);
+    ///     assert!(mocks[0].expectation_satisfied);
+    /// }
+    /// ```
+    ///
+    /// [`register_scoped`]: #method.register_scoped
+    /// [`MockGuard`]: struct.MockGuard.html
+    pub async fn mounted_mocks(&self) -> Vec<MockSummary> {
+        self.mock_actor.mounted_mocks().await
+    }
+
+    /// Return a human-readable dump of the state of this instance of `MockServer`, meant to
+    /// be printed when a test fails.
+    ///
+    /// It lists:
+    /// - the number of requests received so far, matched or not;
+    /// - the mounted `Mock`s (see [`mounted_mocks`]), with their matchers, how many requests
+    ///   they matched and whether their expectations are satisfied;
+    /// - the last few requests that did not match any `Mock` (see [`verify_no_unmatched`]),
+    ///   if request recording is enabled.
+    ///
+    /// The format is meant for humans and it might change: use [`mounted_mocks`] and
+    /// [`received_requests`] to inspect the state of the `MockServer` programmatically.  
+    /// Nothing is computed until `report` is called.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::path;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(path("/hello"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .named("Hello")
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     surf::get(format!("{}/missing", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert - e.g. print it when an assertion fails
+    ///     let report = mock_server.report().await;
+    ///     assert!(report.contains("Hello"));
+    ///     assert!(report.contains("/missing"));
+    /// }
+    /// ```
+    ///
+    /// [`mounted_mocks`]: #method.mounted_mocks
+    /// [`verify_no_unmatched`]: #method.verify_no_unmatched
+    /// [`received_requests`]: #method.received_requests
+    pub async fn report(&self) -> String {
+        let (n_requests, n_unmatched) = self.mock_actor.request_counts().await;
+        let mocks = self.mock_actor.mounted_mocks().await;
+        let (_, unmatched) = self.mock_actor.unmatched_requests().await;
+
+        let mut report = format!(
+            "MockServer listening on {}\nRequests received: {} ({} unmatched)\n",
+            self.uri(),
+            n_requests,
+            n_unmatched
+        );
+
+        report.push_str(&format!("\nMounted mocks ({}):", mocks.len()));
+        for (i, mock) in mocks.iter().enumerate() {
+            report.push_str(&format!(
+                "\n{}. {} [{}]: matched {} request(s), expected {} ({})",
+                i + 1,
+                mock.name.as_deref().unwrap_or("<unnamed>"),
+                mock.matchers.join(", "),
+                mock.n_matched_requests,
+                mock.expectation,
+                if mock.expectation_satisfied {
+                    "satisfied"
+                } else {
+                    "NOT satisfied"
+                }
+            ));
+        }
+
+        if n_unmatched > 0 {
+            if unmatched.is_empty() {
+                report.push_str(
+                    "\n\nUnmatched requests: request recording is disabled, they cannot be listed.",
+                );
+            } else {
+                let skip = unmatched.len().saturating_sub(REPORTED_UNMATCHED_REQUESTS);
+                report.push_str(&format!(
+                    "\n\nUnmatched requests (last {} of {}):",
+                    unmatched.len() - skip,
+                    unmatched.len()
+                ));
+                for (i, request) in unmatched.iter().enumerate().skip(skip) {
+                    report.push_str(&format!("\n\n{}. {}", i + 1, request));
+                }
+            }
+        }
+        report
+    }
+
+    /// Return all the requests received by this instance of `MockServer`, in the order
+    /// they were received, regardless of them matching a mounted `Mock` or not.
+    ///
+    /// It returns `None` if request recording has been disabled via
+    /// [`MockServerBuilder::disable_request_recording`].  
+    /// The log is cleared by [`reset`] and [`reset_counters`].
+    ///
+    /// Each request carries the moment it was received in `received_at`: see
+    /// [`Request::inter_arrival_times`].
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///
+    ///     // Act
+    ///     surf::get(format!("{}/hello", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert
+    ///     let received_requests = mock_server.received_requests().await.unwrap();
+    ///     assert_eq!(received_requests.len(), 1);
+    ///     assert_eq!(received_requests[0].url.path(), "/hello");
+    /// }
+    /// ```
+    ///
+    /// [`MockServerBuilder::disable_request_recording`]: struct.MockServerBuilder.html#method.disable_request_recording
+    /// [`reset`]: #method.reset
+    /// [`reset_counters`]: #method.reset_counters
+    /// [`Request::inter_arrival_times`]: struct.Request.html#method.inter_arrival_times
+    pub async fn received_requests(&self) -> Option<Vec<Request>> {
+        self.mock_actor.recorded_requests(false).await
+    }
+
+    /// Take all the requests received so far by this instance of `MockServer`, clearing
+    /// the log.
+    ///
+    /// Taking and clearing happen atomically: each request is returned by exactly one call,
+    /// even if requests keep coming in.  
+    /// Use it at the end of a test to dump the traffic (e.g. into a file attached to the
+    /// report of a failed test) or between the phases of a test to inspect them separately.
+    ///
+    /// It returns an empty vector if request recording has been disabled via
+    /// [`MockServerBuilder::disable_request_recording`].
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Act
+    ///     let drained = mock_server.drain_received_requests().await;
+    ///
+    ///     // Assert
+    ///     assert_eq!(drained.len(), 1);
+    ///     assert!(mock_server.received_requests().await.unwrap().is_empty());
+    /// }
+    /// ```
+    ///
+    /// [`MockServerBuilder::disable_request_recording`]: struct.MockServerBuilder.html#method.disable_request_recording
+    pub async fn drain_received_requests(&self) -> Vec<Request> {
+        self.mock_actor
+            .recorded_requests(true)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Return a stream yielding every request received by this instance of `MockServer` from
+    /// now on, as soon as it arrives, regardless of it matching a mounted `Mock` or not.
+    ///
+    /// Use it to wait until your client makes a call (e.g. `stream.next().await`) instead of
+    /// polling [`total_requests`] or [`received_requests`] in a loop.  
+    /// Requests are handed to the stream as soon as they have been matched against the
+    /// mounted `Mock`s (see `Request::matched_mock`), before the response is generated: they
+    /// keep being served as usual.  
+    /// Requests received before `request_stream` is called are not yielded - use
+    /// [`received_requests`] to retrieve them. You can open as many streams as you like: each
+    /// of them gets its own copy of every request.
+    ///
+    /// ### Backpressure
+    ///
+    /// There is none: the `MockServer` never waits for a slow consumer. Requests that have not
+    /// been consumed yet are buffered in memory, without bounds, until the stream is polled or
+    /// dropped. Drop the stream once you are done with it.  
+    /// The stream ends when the `MockServer` is shut down.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let mut requests = mock_server.request_stream().await;
+    ///
+    ///     // Act - e.g. your client working in the background
+    ///     let uri = format!("{}/hello", &mock_server.uri());
+    ///     async_std::task::spawn(async move {
+    ///         surf::get(uri).await.unwrap();
+    ///     });
+    ///
+    ///     // Assert - wait for the request to come in
+    ///     let request = requests.next().await.unwrap();
+    ///     assert_eq!(request.url.path(), "/hello");
+    /// }
+    /// ```
+    ///
+    /// [`total_requests`]: #method.total_requests
+    /// [`received_requests`]: #method.received_requests
+    pub async fn request_stream(&self) -> impl Stream<Item = Request> + Unpin {
+        self.mock_actor.subscribe().await
+    }
+
+    fn handle(&self) -> ServerHandle {
+        ServerHandle {
+            server_actor: self.server_actor.clone(),
+            mock_actor: self.mock_actor.clone(),
+            shut_down: self.shut_down.clone(),
+        }
     }
 
     /// Return the base uri of this running instance of `MockServer`, e.g. `http://127.0.0.1:4372`.
@@ -211,6 +924,72 @@ impl MockServer {
         format!("http://{}", self.server_actor.address)
     }
 
+    /// Return the uri of `path` on this running instance of `MockServer`,
+    /// e.g. `http://127.0.0.1:4372/hello` for `hello`.
+    ///
+    /// `path` is joined to the base uri with exactly one slash, regardless of it having a
+    /// leading slash or not. It can include a query string.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::{path, query_param};
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(path("/hello"))
+    ///         .and(query_param("name", "world"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let status = surf::get(mock_server.uri_for("hello?name=world"))
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(status.as_u16(), 200);
+    /// }
+    /// ```
+    pub fn uri_for(&self, path: &str) -> String {
+        format!("{}/{}", self.uri(), path.trim_start_matches('/'))
+    }
+
+    /// Return the base url of this running instance of `MockServer` as a typed `Url`,
+    /// e.g. `http://127.0.0.1:4372/`.
+    ///
+    /// Use this method to compose urls with `Url::join` instead of concatenating strings.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::path;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(path("/api/v1/users"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let url = mock_server.url().join("/api/v1/users").unwrap();
+    ///     let status = surf::get(url).await.unwrap().status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(status.as_u16(), 200);
+    /// }
+    /// ```
+    pub fn url(&self) -> Url {
+        Url::parse(&self.uri()).expect("Failed to parse the mock server uri.")
+    }
+
     /// Return the socket address of this running instance of `MockServer`, e.g. `127.0.0.1:4372`.
     ///
     /// Use this method to interact with the `MockServer` using `TcpStream`s.
@@ -237,19 +1016,6 @@ impl MockServer {
 impl Drop for MockServer {
     // Clean up when the `MockServer` instance goes out of scope.
     fn drop(&mut self) {
-        debug!("Verify mock expectations.");
-        if !run!(self.verify()) {
-            if std::thread::panicking() {
-                debug!("Verification failed: mock expectations have not been satisfied.");
-            } else {
-                panic!("Verification failed: mock expectations have not been satisfied.");
-            }
-        }
-        debug!("Killing server actor.");
-        self.server_actor.actor_ref.kill().unwrap();
-        debug!("Killed server actor.");
-        debug!("Killing mock actor.");
-        self.mock_actor.actor_ref.kill().unwrap();
-        debug!("Killed mock actor.");
+        check_verification(self.handle().shut_down());
     }
 }
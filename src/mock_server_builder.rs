@@ -0,0 +1,629 @@
+use crate::connection::ConnectionSettings;
+use crate::{Error, MockServer, Request};
+use http_types::Response;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A builder providing a fluent API to configure a [`MockServer`] before starting it.
+///
+/// You can get an instance of `MockServerBuilder` using [`MockServer::builder`].
+///
+/// [`MockServer`]: struct.MockServer.html
+/// [`MockServer::builder`]: struct.MockServer.html#method.builder
+#[derive(Debug, Default)]
+pub struct MockServerBuilder {
+    pub(crate) rng_seed: Option<u64>,
+    pub(crate) accept_concurrency: Option<usize>,
+    pub(crate) listen_backlog: Option<u32>,
+    pub(crate) decompress_request_bodies: bool,
+    pub(crate) disable_request_recording: bool,
+    pub(crate) not_found_body: Option<Vec<u8>>,
+    pub(crate) request_middlewares: Vec<RequestMiddleware>,
+    pub(crate) response_middlewares: Vec<ResponseMiddleware>,
+    pub(crate) connection_settings: ConnectionSettings,
+    // Set for `MockServer`s outliving any single test (see `LazyMockServer`): they must not
+    // be shut down by the `TestScope` they happen to be started in.
+    pub(crate) skip_test_scope: bool,
+}
+
+impl MockServerBuilder {
+    /// Seed the random number generator used by the randomized features of the [`MockServer`],
+    /// making their behaviour deterministic and your test suite reproducible.
+    ///
+    /// By default, randomness is sourced from entropy.
+    ///
+    /// Each mounted `Mock` gets its own seed, derived from `seed` and from the order in which
+    /// mocks are mounted: mounting the same mocks in the same order gives you the same
+    /// sequence of responses.
+    ///
+    /// The following features consume the random number generator:
+    /// - [`responders::weighted`], unless it has been explicitly seeded with its own `seed` method;
+    /// - [`responders::recovering`], same as above;
+    /// - custom responders that override [`Respond::reseed`].
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    /// use wiremock::responders::weighted;
+    ///
+    /// // Start a server seeded with `seed` and return the statuses of the first responses.
+    /// async fn statuses(seed: u64) -> Vec<u16> {
+    ///     let mock_server = MockServer::builder().rng_seed(seed).start().await;
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(weighted(vec![
+    ///             (ResponseTemplate::new(200), 1),
+    ///             (ResponseTemplate::new(500), 1),
+    ///         ]))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     let mut statuses = vec![];
+    ///     for _ in 0..5 {
+    ///         let status = surf::get(&mock_server.uri()).await.unwrap().status();
+    ///         statuses.push(status.as_u16());
+    ///     }
+    ///     statuses
+    /// }
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Act - two servers, built with the same seed
+    ///     let first = statuses(42).await;
+    ///     let second = statuses(42).await;
+    ///
+    ///     // Assert - the same sequence of outcomes, run after run
+    ///     assert_eq!(first, second);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`responders::weighted`]: responders/fn.weighted.html
+    /// [`responders::recovering`]: responders/fn.recovering.html
+    /// [`Respond::reseed`]: trait.Respond.html#method.reseed
+    pub fn rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// Limit the number of connections served in parallel by the [`MockServer`].
+    ///
+    /// By default there is no limit: every accepted connection is served straight away on its
+    /// own task.  
+    /// Once `n` connections are being served, new connections are left waiting in the listen
+    /// backlog (see [`listen_backlog`]) until one of the active connections is closed.
+    /// Keep in mind that connections are kept alive between requests: a client holding on to
+    /// an idle connection keeps its slot busy.
+    ///
+    /// It can be used to simulate a server with limited capacity or to keep resource usage
+    /// in check when hammering the [`MockServer`] in a stress test.
+    ///
+    /// It panics if `n` is 0.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let mock_server = MockServer::builder()
+    ///         .accept_concurrency(16)
+    ///         .listen_backlog(1024)
+    ///         .start()
+    ///         .await;
+    ///
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///     assert_eq!(status.as_u16(), 404);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`listen_backlog`]: #method.listen_backlog
+    pub fn accept_concurrency(mut self, n: usize) -> Self {
+        assert!(n > 0, "The accept concurrency must be greater than 0.");
+        self.accept_concurrency = Some(n);
+        self
+    }
+
+    /// Set the size of the TCP listen backlog of the [`MockServer`]: the maximum number of
+    /// pending connections, established by the operating system but not yet accepted by
+    /// the [`MockServer`].
+    ///
+    /// It defaults to 128, the same value used by the standard library.  
+    /// When the backlog is full, new connection attempts are refused (or time out, depending
+    /// on the platform): you might want to increase it if you are opening many connections
+    /// at once.  
+    /// The operating system might cap the value you specify (e.g. `net.core.somaxconn` on Linux).
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub fn listen_backlog(mut self, backlog: u32) -> Self {
+        self.listen_backlog = Some(backlog);
+        self
+    }
+
+    /// Close connections that have been idle for longer than `timeout`.
+    ///
+    /// A connection is idle when the [`MockServer`] is waiting for the client to send its
+    /// next request: right after the connection has been accepted or after a response has
+    /// been sent. If the request has not been received in full (head and body) within
+    /// `timeout`, the connection is closed without a response.
+    ///
+    /// It defaults to 60 seconds: clients opening connections and never using them (or
+    /// forgetting to close them) cannot make the [`MockServer`] accumulate connections
+    /// without bounds.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use std::time::Duration;
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let mock_server = MockServer::builder()
+    ///         .idle_timeout(Duration::from_secs(5))
+    ///         .start()
+    ///         .await;
+    ///
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///     assert_eq!(status.as_u16(), 404);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_settings.idle_timeout = timeout;
+        self
+    }
+
+    /// Respond to requests as soon as their head (request line and headers) has been
+    /// received, without waiting for the body.
+    ///
+    /// Some servers reject an upload (e.g. with a `401` or a `413`) before consuming its
+    /// body: use this to check how your client copes with a server short-circuiting it.
+    ///
+    /// The exchange becomes half-duplex:
+    /// - matchers run against the head only - `Request::body` is always empty, hence body
+    ///   matchers never match;
+    /// - no `100 Continue` is sent to clients asking for it: they get the final response
+    ///   straight away;
+    /// - the response carries `Connection: close` and is written while the [`MockServer`]
+    ///   keeps reading (and discarding) the body, so a client still streaming it never
+    ///   blocks on a full socket buffer;
+    /// - the connection is closed once the body has been consumed, or after the
+    ///   [`idle_timeout`] if the client stops sending it.
+    ///
+    /// Requests without a body are served as usual.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder()
+    ///         .respond_before_reading_body()
+    ///         .start()
+    ///         .await;
+    ///     Mock::given(method("POST"))
+    ///         .respond_with(ResponseTemplate::new(401))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let status = surf::post(&mock_server.uri())
+    ///         .body_bytes(vec![0u8; 1024])
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(status.as_u16(), 401);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`idle_timeout`]: #method.idle_timeout
+    pub fn respond_before_reading_body(mut self) -> Self {
+        self.connection_settings.respond_before_body = true;
+        self
+    }
+
+    /// Close each connection after serving `n` requests on it.
+    ///
+    /// By default connections are kept alive for as long as the client wants them (see
+    /// [`idle_timeout`]).  
+    /// With a limit in place, the `n`-th response on a connection carries `Connection: close`
+    /// and the [`MockServer`] closes the connection right after sending it: the client has to
+    /// open a new one for its next request. Use it to exercise the connection pooling logic of
+    /// your client.
+    ///
+    /// It panics if `n` is 0.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder()
+    ///         .max_requests_per_connection(1)
+    ///         .start()
+    ///         .await;
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let response = surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.status().as_u16(), 200);
+    ///     assert_eq!(response.header("connection"), Some("close"));
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`idle_timeout`]: #method.idle_timeout
+    pub fn max_requests_per_connection(mut self, n: usize) -> Self {
+        assert!(
+            n > 0,
+            "The maximum number of requests per connection must be greater than 0."
+        );
+        self.connection_settings.max_requests = Some(n);
+        self
+    }
+
+    /// Speak HTTP/1.0: accept HTTP/1.0 requests and close the connection after each response.
+    ///
+    /// Legacy clients open a new connection for every request and rely on the server closing
+    /// it to know where the response ends: use this to check that your client copes with a
+    /// server that does not keep connections alive.
+    ///
+    /// Once enabled:
+    /// - HTTP/1.0 requests are served (they get a `400 Bad Request` otherwise), as well as
+    ///   HTTP/1.1 ones;
+    /// - responses start with `HTTP/1.0`, carry `Connection: close` and the connection is
+    ///   closed right after them;
+    /// - bodies are always sent with a `Content-Length`: HTTP/1.0 has no chunked encoding,
+    ///   hence trailers (see [`ResponseTemplate::append_trailer`]) are dropped;
+    /// - no `100 Continue` is sent to clients asking for it.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder().http_1_0().start().await;
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let response = surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.status().as_u16(), 200);
+    ///     assert_eq!(response.header("connection"), Some("close"));
+    /// }
+    /// ```
+    ///
+    /// [`ResponseTemplate::append_trailer`]: struct.ResponseTemplate.html#method.append_trailer
+    pub fn http_1_0(mut self) -> Self {
+        self.connection_settings.http_1_0 = true;
+        self
+    }
+
+    /// Decompress the body of incoming requests, according to their `Content-Encoding` header,
+    /// before running matchers against them.
+    ///
+    /// It lets body matchers (e.g. [`body_json`]) work with clients compressing their
+    /// request bodies.  
+    /// The `Content-Encoding` header is left in place, while `Request::body` holds the
+    /// decompressed bytes.
+    ///
+    /// Each codec must be enabled using the corresponding feature of `wiremock`:
+    /// - `gzip`, for `Content-Encoding: gzip`;
+    /// - `deflate`, for `Content-Encoding: deflate`;
+    /// - `brotli`, for `Content-Encoding: br`.
+    ///
+    /// Bodies using an encoding that is not enabled (or that fail to decode) are passed to
+    /// matchers as they were received.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let mock_server = MockServer::builder()
+    ///         .decompress_request_bodies()
+    ///         .start()
+    ///         .await;
+    ///
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///     assert_eq!(status.as_u16(), 404);
+    /// }
+    /// ```
+    ///
+    /// [`body_json`]: matchers/fn.body_json.html
+    pub fn decompress_request_bodies(mut self) -> Self {
+        self.decompress_request_bodies = true;
+        self
+    }
+
+    /// Stop the [`MockServer`] from keeping a log of the requests it receives.
+    ///
+    /// By default every incoming request is recorded, to be retrieved with
+    /// [`received_requests`] or [`drain_received_requests`]: the log grows without bounds
+    /// until the [`MockServer`] is reset or dropped.  
+    /// Disable recording if you are sending a large number of requests (e.g. in a load test)
+    /// and you do not need them.  
+    /// The requests matched by scoped `Mock`s (see [`MockGuard::received_requests`]) are not
+    /// recorded either, while request counts (e.g. [`total_requests`]) are not affected.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder()
+    ///         .disable_request_recording()
+    ///         .start()
+    ///         .await;
+    ///
+    ///     // Act
+    ///     surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert!(mock_server.received_requests().await.is_none());
+    ///     assert!(mock_server.drain_received_requests().await.is_empty());
+    ///     assert_eq!(mock_server.total_requests().await, 1);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`received_requests`]: struct.MockServer.html#method.received_requests
+    /// [`drain_received_requests`]: struct.MockServer.html#method.drain_received_requests
+    /// [`total_requests`]: struct.MockServer.html#method.total_requests
+    /// [`MockGuard::received_requests`]: struct.MockGuard.html#method.received_requests
+    pub fn disable_request_recording(mut self) -> Self {
+        self.disable_request_recording = true;
+        self
+    }
+
+    /// Set the body of the response returned by the [`MockServer`] when a request does not
+    /// match any of the mounted `Mock`s.
+    ///
+    /// By default, unmatched requests get back a `404 Not Found` with an empty body.  
+    /// Use `not_found_body` to include a message pointing whoever is debugging a failing test
+    /// in the right direction (e.g. "no mock matched, check the mounted mocks") without
+    /// mounting a catch-all `Mock`.  
+    /// The status code is still `404`.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::MockServer;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder()
+    ///         .not_found_body("No mock matched this request.")
+    ///         .start()
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut response = surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.status().as_u16(), 404);
+    ///     assert_eq!(
+    ///         response.body_string().await.unwrap(),
+    ///         "No mock matched this request."
+    ///     );
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub fn not_found_body<B>(mut self, body: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.not_found_body = Some(body.into());
+        self
+    }
+
+    /// Transform every request received by the [`MockServer`] before it is matched against the
+    /// mounted `Mock`s, e.g. to normalise (or strip) volatile headers such as timestamps and
+    /// nonces instead of working around them in every matcher.
+    ///
+    /// `f` gets the incoming request and returns the request the [`MockServer`] should act
+    /// upon: it is the one that gets recorded (see [`received_requests`]), matched and handed
+    /// to responders and [`response_middleware`]s - the original is gone.  
+    /// Request bodies are decompressed (see [`decompress_request_bodies`]) before `f` runs.  
+    /// Calling `request_middleware` more than once stacks the middlewares: they run in the
+    /// order they were added, each of them getting the request returned by the previous one.  
+    /// A panic in `f` is handled like a panic in a custom matcher.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::header;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder()
+    ///         .request_middleware(|mut request| {
+    ///             // Nonces change with every request: we only care about there being one.
+    ///             let nonce = "x-nonce".parse().unwrap();
+    ///             if let Some(values) = request.headers.get_mut(&nonce) {
+    ///                 *values = vec!["<nonce>".parse().unwrap()];
+    ///             }
+    ///             request
+    ///         })
+    ///         .start()
+    ///         .await;
+    ///     Mock::given(header("x-nonce", "<nonce>"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let status = surf::get(&mock_server.uri())
+    ///         .set_header("x-nonce", "d8e8fca2dc0f896f")
+    ///         .await
+    ///         .unwrap()
+    ///         .status();
+    ///
+    ///     // Assert
+    ///     assert_eq!(status.as_u16(), 200);
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`received_requests`]: struct.MockServer.html#method.received_requests
+    /// [`response_middleware`]: #method.response_middleware
+    /// [`decompress_request_bodies`]: #method.decompress_request_bodies
+    pub fn request_middleware<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Request) -> Request + Send + Sync + 'static,
+    {
+        self.request_middlewares
+            .push(RequestMiddleware(Arc::new(f)));
+        self
+    }
+
+    /// Transform every response sent by the [`MockServer`], e.g. to add headers that all the
+    /// responses of the API you are mocking carry (a request id, a `Server` header, etc.)
+    /// without repeating them in every [`ResponseTemplate`].
+    ///
+    /// `f` gets the incoming request and the response generated for it, and returns the
+    /// response to be sent.  
+    /// It runs:
+    /// - after the responder of the matching `Mock` has produced its response, before the
+    ///   delay (see [`ResponseTemplate::set_delay`]) is applied;
+    /// - for requests that did not match any `Mock`, on the default `404` (see
+    ///   [`not_found_body`]).
+    ///
+    /// It does not run on the responses sent by the [`MockServer`] on its own when something
+    /// goes wrong (e.g. a `400` for a request it cannot parse).  
+    /// Calling `response_middleware` more than once stacks the middlewares: they run in the
+    /// order they were added, each of them getting the response returned by the previous one.  
+    /// A panic in `f` is handled like a panic in a custom responder.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::path;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::builder()
+    ///         .response_middleware(|_request, mut response| {
+    ///             response.insert_header("x-request-id", "42").unwrap();
+    ///             response
+    ///         })
+    ///         .start()
+    ///         .await;
+    ///     Mock::given(path("/hello"))
+    ///         .respond_with(ResponseTemplate::new(200))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let matched = surf::get(format!("{}/hello", &mock_server.uri())).await.unwrap();
+    ///     let unmatched = surf::get(format!("{}/missing", &mock_server.uri())).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(matched.header("x-request-id"), Some("42"));
+    ///     assert_eq!(unmatched.status().as_u16(), 404);
+    ///     assert_eq!(unmatched.header("x-request-id"), Some("42"));
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`ResponseTemplate`]: struct.ResponseTemplate.html
+    /// [`ResponseTemplate::set_delay`]: struct.ResponseTemplate.html#method.set_delay
+    /// [`not_found_body`]: #method.not_found_body
+    pub fn response_middleware<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Request, Response) -> Response + Send + Sync + 'static,
+    {
+        self.response_middlewares
+            .push(ResponseMiddleware(Arc::new(f)));
+        self
+    }
+
+    /// Finalise the builder and start the [`MockServer`].
+    ///
+    /// `start` is an asynchronous method, make sure to `.await` it!
+    ///
+    /// It panics if the [`MockServer`] cannot be started - use [`try_start`]
+    /// if you'd rather get an error back.
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`try_start`]: #method.try_start
+    pub async fn start(self) -> MockServer {
+        self.try_start().await.unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Finalise the builder and start the [`MockServer`], returning an [`Error`] if it
+    /// cannot be started (e.g. there is no port available).
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`Error`]: enum.Error.html
+    pub async fn try_start(self) -> Result<MockServer, Error> {
+        MockServer::start_with(self).await
+    }
+}
+
+/// Wrapper around the closure passed to `MockServerBuilder::request_middleware`.
+///
+/// Same as `MatchCallback`: we need a (fake) implementation of `Debug`.
+#[derive(Clone)]
+pub(crate) struct RequestMiddleware(pub(crate) Arc<RequestMiddlewareFn>);
+
+type RequestMiddlewareFn = dyn Fn(Request) -> Request + Send + Sync;
+
+impl Debug for RequestMiddleware {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestMiddleware")
+    }
+}
+
+/// Wrapper around the closure passed to `MockServerBuilder::response_middleware`.
+///
+/// Same as `MatchCallback`: we need a (fake) implementation of `Debug`.
+#[derive(Clone)]
+pub(crate) struct ResponseMiddleware(pub(crate) Arc<ResponseMiddlewareFn>);
+
+type ResponseMiddlewareFn = dyn Fn(&Request, Response) -> Response + Send + Sync;
+
+impl Debug for ResponseMiddleware {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ResponseMiddleware")
+    }
+}
@@ -1,6 +1,7 @@
 use async_std::prelude::*;
 use http_types::headers::{HeaderName, HeaderValue};
 use http_types::{Method, Url};
+use std::time::{Duration, Instant};
 use std::{collections::HashMap, fmt};
 
 /// An incoming request to an instance of [`MockServer`].
@@ -21,17 +22,57 @@ use std::{collections::HashMap, fmt};
 /// We introduce our `Request` type to perform this extraction once when the request
 /// arrives in the mock serve, store the result and pass an immutable reference to it
 /// to all our matchers.
-#[derive(Debug)]
+///
+/// ### Raw request line:
+/// `raw_method` and `raw_target` hold the method and the request target exactly as they
+/// appeared in the request line sent by the client.  
+/// `http_types::Method` only covers the standard HTTP methods: requests using an extension
+/// method (e.g. WebDAV's `PROPFIND`) have `method` set to `None` - use `raw_method`
+/// (or the [`method_str`] matcher) to tell them apart - see the changelog to migrate from
+/// 0.2, where `method` was a plain `Method`.  
+/// The request target can be in absolute form (e.g. `GET http://example.com/path HTTP/1.1`,
+/// as sent to a proxy): `url` is then built from the target itself.
+///
+/// ### Headers:
+/// `headers` maps each header name to the list of its values, one for each time the header
+/// appears in the request, in the order they were received.  
+/// Values are not split on commas: `Accept: a, b` is a single value.
+///
+/// ### Timestamp:
+/// `received_at` is the moment the [`MockServer`] finished reading the request (head and
+/// body) from the wire.  
+/// Use it, on the requests returned by [`MockServer::received_requests`], to assert on
+/// timing-sensitive behaviour of your client (e.g. debouncing or batching) - see
+/// [`inter_arrival_times`].
+///
+/// ### Matched mock:
+/// `matched_mock` tells you which [`Mock`] handled the request: its name, if it was given one
+/// via [`Mock::named`], or `unnamed mock #<n>` (`n` being its position in the order mocks were
+/// registered, starting from 0). It is `None` if no mock matched.  
+/// It is only known once matching is over: it is always `None` in the requests handed to
+/// matchers and responders, and set in the requests you get back from
+/// [`MockServer::received_requests`] and from the other request logs.
+///
+/// [`MockServer::received_requests`]: struct.MockServer.html#method.received_requests
+/// [`inter_arrival_times`]: #method.inter_arrival_times
+/// [`method_str`]: matchers/fn.method_str.html
+/// [`Mock`]: struct.Mock.html
+/// [`Mock::named`]: struct.Mock.html#method.named
+#[derive(Debug, Clone)]
 pub struct Request {
     pub url: Url,
-    pub method: Method,
+    pub method: Option<Method>,
+    pub raw_method: String,
+    pub raw_target: String,
     pub headers: HashMap<HeaderName, Vec<HeaderValue>>,
     pub body: Vec<u8>,
+    pub received_at: Instant,
+    pub matched_mock: Option<String>,
 }
 
 impl fmt::Display for Request {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "{} {}", self.method, self.url)?;
+        writeln!(f, "{} {}", self.raw_method, self.url)?;
         for (name, values) in &self.headers {
             let values = values
                 .iter()
@@ -48,6 +89,11 @@ impl Request {
     pub async fn from(mut request: http_types::Request) -> Request {
         let method = request.method();
         let url = request.url().to_owned();
+        let raw_method = method.to_string();
+        let raw_target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_owned(),
+        };
 
         let mut headers = HashMap::new();
         for (header_name, header_values) in &request {
@@ -64,9 +110,54 @@ impl Request {
 
         Self {
             url,
-            method,
+            method: Some(method),
+            raw_method,
+            raw_target,
             headers,
             body,
+            received_at: Instant::now(),
+            matched_mock: None,
         }
     }
+
+    /// The time elapsed between each request of `requests` and the one before it, based
+    /// on `received_at`.
+    ///
+    /// It returns one `Duration` less than the number of requests: the first element is
+    /// the time between `requests[0]` and `requests[1]`, and so on.  
+    /// Pairs that are out of order (i.e. the later one was received first) give a zero
+    /// `Duration`.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use std::time::Duration;
+    /// use wiremock::{MockServer, Request};
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///
+    ///     // Act
+    ///     surf::get(&mock_server.uri()).await.unwrap();
+    ///     async_std::task::sleep(Duration::from_millis(100)).await;
+    ///     surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     let requests = mock_server.received_requests().await.unwrap();
+    ///     let intervals = Request::inter_arrival_times(&requests);
+    ///     assert_eq!(intervals.len(), 1);
+    ///     assert!(intervals[0] >= Duration::from_millis(100));
+    /// }
+    /// ```
+    pub fn inter_arrival_times(requests: &[Request]) -> Vec<Duration> {
+        requests
+            .windows(2)
+            .map(|pair| {
+                pair[1]
+                    .received_at
+                    .saturating_duration_since(pair[0].received_at)
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,111 @@
+use crate::{Request, ResponseTemplate};
+use std::fmt::{Debug, Formatter};
+
+/// Anything that implements `Respond` can be used to reply to an incoming request when a
+/// [`Mock`] is activated.
+///
+/// A [`ResponseTemplate`] always returns the same response, regardless of the incoming request.
+/// `Respond` gives you a hook to compute a response on the fly, for every incoming request,
+/// when what you need is not covered by the [`responders`] provided out-of-the-box.
+///
+/// ```rust
+/// use wiremock::{MockServer, Mock, Request, Respond, ResponseTemplate};
+/// use wiremock::matchers::method;
+///
+/// // Echo back the body of the incoming request.
+/// pub struct EchoResponder;
+///
+/// impl Respond for EchoResponder {
+///     fn respond(&self, request: &Request) -> ResponseTemplate {
+///         ResponseTemplate::new(200).set_body_bytes(request.body.clone())
+///     }
+/// }
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     Mock::given(method("POST"))
+///         .respond_with(EchoResponder)
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let mut response = surf::post(&mock_server.uri())
+///         .body_string("hello".into())
+///         .await
+///         .unwrap();
+///
+///     // Assert
+///     assert_eq!(response.status().as_u16(), 200);
+///     assert_eq!(response.body_string().await.unwrap(), "hello");
+/// }
+/// ```
+///
+/// Anonymous functions that take a reference to a [`Request`] as input and return a
+/// [`ResponseTemplate`] as output automatically implement the `Respond` trait.
+///
+/// [`Mock`]: struct.Mock.html
+/// [`Request`]: struct.Request.html
+/// [`ResponseTemplate`]: struct.ResponseTemplate.html
+/// [`responders`]: responders/index.html
+pub trait Respond: Send + Sync {
+    /// Given a reference to a `Request`, return the `ResponseTemplate` that should be used
+    /// to build the response.
+    fn respond(&self, request: &Request) -> ResponseTemplate;
+
+    /// Re-seed the random number generator used by this responder, if any.
+    ///
+    /// It is called when the responder is mounted on a [`MockServer`] configured with
+    /// [`rng_seed`] - the seed is derived from the one of the [`MockServer`].
+    ///
+    /// The default implementation does nothing: you only need to override it if your
+    /// responder takes random decisions and you want them to be reproducible.
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`rng_seed`]: struct.MockServerBuilder.html#method.rng_seed
+    fn reseed(&mut self, _seed: u64) {}
+}
+
+impl Respond for ResponseTemplate {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        self.clone()
+    }
+}
+
+/// Implement the `Respond` trait for all closures, out of the box,
+/// if their signature is compatible.
+impl<F> Respond for F
+where
+    F: Fn(&Request) -> ResponseTemplate,
+    F: Send + Sync,
+{
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        // Just call the closure itself!
+        self(request)
+    }
+}
+
+/// Wrapper around a `Respond` trait object.
+///
+/// Same as `Matcher`: we need a (fake) implementation of `Debug` to pass it around
+/// as part of a `bastion` message.
+pub(crate) struct Responder(pub(crate) Box<dyn Respond>);
+
+impl Respond for Responder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        self.0.respond(request)
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.0.reseed(seed)
+    }
+}
+
+impl Debug for Responder {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Dummy `Debug` implementation, see `Matcher` for the rationale.
+        Ok(())
+    }
+}
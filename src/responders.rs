@@ -0,0 +1,389 @@
+//! A collection of different responding strategies provided out-of-the-box by `wiremock`.
+//!
+//! A [`ResponseTemplate`] returns the same response to all matching requests - the responders
+//! in this module compute the response on a per-request basis.
+//!
+//! If the set of responders provided out-of-the-box is not enough for your specific testing needs
+//! you can implement your own thanks to the [`Respond`] trait.
+//!
+//! [`Respond`]: ../trait.Respond.html
+//! [`ResponseTemplate`]: ../struct.ResponseTemplate.html
+use crate::{Request, Respond, ResponseTemplate};
+use http_types::headers::HeaderName;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::Mutex;
+
+#[derive(Debug)]
+/// Pick a response template at random, for each matching request, according to the specified
+/// weights.
+///
+/// It can be used to model a flaky upstream dependency (e.g. a 500 returned 10% of the time)
+/// without having to set up a stateful sequence of mocks.
+///
+/// Randomness is sourced from entropy by default: use [`seed`] (or [`rng_seed`] on the
+/// [`MockServer`]) to make the sequence of responses reproducible.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::method;
+/// use wiremock::responders::weighted;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     // 1 request out of 10, on average, will get back a 500.
+///     let responder = weighted(vec![
+///         (ResponseTemplate::new(200), 9),
+///         (ResponseTemplate::new(500), 1),
+///     ])
+///     .seed(42);
+///     Mock::given(method("GET"))
+///         .respond_with(responder)
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(&mock_server.uri())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert!(status.as_u16() == 200 || status.as_u16() == 500);
+/// }
+/// ```
+///
+/// [`seed`]: #method.seed
+/// [`rng_seed`]: ../struct.MockServerBuilder.html#method.rng_seed
+/// [`MockServer`]: ../struct.MockServer.html
+pub struct WeightedResponder {
+    templates: Vec<ResponseTemplate>,
+    weights: WeightedIndex<u32>,
+    rng: Mutex<StdRng>,
+    // Set to `true` when a seed has been explicitly specified via `seed`: it takes precedence
+    // over the seed of the `MockServer`.
+    seeded: bool,
+}
+
+/// Shorthand for [`WeightedResponder::new`](struct.WeightedResponder.html).
+pub fn weighted<I>(choices: I) -> WeightedResponder
+where
+    I: IntoIterator<Item = (ResponseTemplate, u32)>,
+{
+    WeightedResponder::new(choices)
+}
+
+impl WeightedResponder {
+    /// Specify the response templates to choose from, each paired with its weight.
+    ///
+    /// The probability of a template being picked is its weight divided by the sum of all
+    /// weights.
+    ///
+    /// It panics if no template is provided or if all weights are zero.
+    pub fn new<I>(choices: I) -> Self
+    where
+        I: IntoIterator<Item = (ResponseTemplate, u32)>,
+    {
+        let (templates, weights): (Vec<_>, Vec<_>) = choices.into_iter().unzip();
+        let weights = WeightedIndex::new(weights).expect("Failed to build weighted distribution.");
+        Self {
+            templates,
+            weights,
+            rng: Mutex::new(StdRng::from_entropy()),
+            seeded: false,
+        }
+    }
+
+    /// Seed the random number generator used to pick response templates, making the
+    /// sequence of responses deterministic.
+    ///
+    /// It takes precedence over the seed specified via [`rng_seed`] on the [`MockServer`].
+    ///
+    /// [`rng_seed`]: ../struct.MockServerBuilder.html#method.rng_seed
+    /// [`MockServer`]: ../struct.MockServer.html
+    pub fn seed(self, seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            seeded: true,
+            ..self
+        }
+    }
+}
+
+impl Respond for WeightedResponder {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        let mut rng = self.rng.lock().unwrap();
+        let index = self.weights.sample(&mut *rng);
+        self.templates[index].clone()
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        if !self.seeded {
+            self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Simulate a backend recovering from an outage: each matching request fails with a probability
+/// that decays, call after call.
+///
+/// The `n`-th matching request (starting from 0) gets the failure template (a `500` by
+/// default) with probability
+///
+/// ```text
+/// initial_failure_rate * decay^n
+/// ```
+///
+/// and the success template (a `200` by default) otherwise: early requests mostly fail,
+/// later ones mostly succeed. With `decay` set to `0.5`, the failure rate halves with every
+/// call.  
+/// It exercises the backoff-and-retry logic of your client the way a real recovering service
+/// would, without a hand-crafted sequence of mocks.
+///
+/// Randomness is sourced from entropy by default: use [`seed`] (or [`rng_seed`] on the
+/// [`MockServer`]) to make the sequence of responses reproducible.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::method;
+/// use wiremock::responders::recovering;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     // The first request always fails, the failure rate then halves with every call.
+///     let responder = recovering(1.0, 0.5)
+///         .templates(ResponseTemplate::new(200), ResponseTemplate::new(503))
+///         .seed(42);
+///     Mock::given(method("GET"))
+///         .respond_with(responder)
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(&mock_server.uri())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 503);
+/// }
+/// ```
+///
+/// [`seed`]: #method.seed
+/// [`rng_seed`]: ../struct.MockServerBuilder.html#method.rng_seed
+/// [`MockServer`]: ../struct.MockServer.html
+pub struct RecoveringResponder {
+    initial_failure_rate: f64,
+    decay: f64,
+    success: ResponseTemplate,
+    failure: ResponseTemplate,
+    // The random number generator and the number of requests we responded to so far.
+    state: Mutex<(StdRng, i32)>,
+    // Set to `true` when a seed has been explicitly specified via `seed`: it takes precedence
+    // over the seed of the `MockServer`.
+    seeded: bool,
+}
+
+/// Shorthand for [`RecoveringResponder::new`](struct.RecoveringResponder.html).
+pub fn recovering(initial_failure_rate: f64, decay: f64) -> RecoveringResponder {
+    RecoveringResponder::new(initial_failure_rate, decay)
+}
+
+impl RecoveringResponder {
+    /// Specify the failure rate of the first request and the factor it gets multiplied by
+    /// after each request.
+    ///
+    /// It panics if either of them is not between 0 and 1 (inclusive).
+    pub fn new(initial_failure_rate: f64, decay: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&initial_failure_rate),
+            "The initial failure rate must be between 0 and 1."
+        );
+        assert!(
+            (0.0..=1.0).contains(&decay),
+            "The decay must be between 0 and 1."
+        );
+        Self {
+            initial_failure_rate,
+            decay,
+            success: ResponseTemplate::new(200),
+            failure: ResponseTemplate::new(500),
+            state: Mutex::new((StdRng::from_entropy(), 0)),
+            seeded: false,
+        }
+    }
+
+    /// Replace the default templates (a `200` on success, a `500` on failure).
+    pub fn templates(self, success: ResponseTemplate, failure: ResponseTemplate) -> Self {
+        Self {
+            success,
+            failure,
+            ..self
+        }
+    }
+
+    /// Seed the random number generator used to decide which requests fail, making the
+    /// sequence of responses deterministic.
+    ///
+    /// It takes precedence over the seed specified via [`rng_seed`] on the [`MockServer`].
+    ///
+    /// [`rng_seed`]: ../struct.MockServerBuilder.html#method.rng_seed
+    /// [`MockServer`]: ../struct.MockServer.html
+    pub fn seed(self, seed: u64) -> Self {
+        Self {
+            state: Mutex::new((StdRng::seed_from_u64(seed), 0)),
+            seeded: true,
+            ..self
+        }
+    }
+}
+
+impl Respond for RecoveringResponder {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        let mut state = self.state.lock().unwrap();
+        let (rng, n_calls) = &mut *state;
+        let failure_rate = self.initial_failure_rate * self.decay.powi(*n_calls);
+        *n_calls = n_calls.saturating_add(1);
+        if rng.gen_bool(failure_rate) {
+            self.failure.clone()
+        } else {
+            self.success.clone()
+        }
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        if !self.seeded {
+            self.state = Mutex::new((StdRng::seed_from_u64(seed), 0));
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Pick a response template according to the value of a header of the incoming request,
+/// e.g. to serve JSON or XML depending on `Accept`.
+///
+/// The value of the header is compared **exactly** (case-sensitive, surrounding whitespace
+/// trimmed) with the keys of `templates`. If the header is repeated, the first value with
+/// a template wins.  
+/// Requests without the header, or with a value that has no template, get `default`.
+///
+/// It spares you from mounting one [`Mock`] per header value, each with the same matchers.
+///
+/// ### Example:
+/// ```rust
+/// use std::collections::HashMap;
+/// use wiremock::{MockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::method;
+/// use wiremock::responders::by_header;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = MockServer::start().await;
+///
+///     let mut templates = HashMap::new();
+///     templates.insert(
+///         "application/json",
+///         ResponseTemplate::new(200).set_body_raw(r#"{"hello": "world"}"#, "application/json"),
+///     );
+///     templates.insert(
+///         "application/xml",
+///         ResponseTemplate::new(200).set_body_raw("<hello>world</hello>", "application/xml"),
+///     );
+///     Mock::given(method("GET"))
+///         .respond_with(by_header("Accept", templates, ResponseTemplate::new(406)))
+///         .mount(&mock_server)
+///         .await;
+///
+///     // Act
+///     let mut xml = surf::get(&mock_server.uri())
+///         .set_header("Accept", "application/xml")
+///         .await
+///         .unwrap();
+///     let unsupported = surf::get(&mock_server.uri())
+///         .set_header("Accept", "text/csv")
+///         .await
+///         .unwrap();
+///
+///     // Assert
+///     assert_eq!(xml.body_string().await.unwrap(), "<hello>world</hello>");
+///     assert_eq!(unsupported.status().as_u16(), 406);
+/// }
+/// ```
+///
+/// [`Mock`]: ../struct.Mock.html
+pub struct HeaderResponder {
+    header_name: HeaderName,
+    templates: HashMap<String, ResponseTemplate>,
+    default: ResponseTemplate,
+}
+
+/// Shorthand for [`HeaderResponder::new`](struct.HeaderResponder.html).
+pub fn by_header<K, I, V>(
+    header_name: K,
+    templates: I,
+    default: ResponseTemplate,
+) -> HeaderResponder
+where
+    K: TryInto<HeaderName>,
+    <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+    I: IntoIterator<Item = (V, ResponseTemplate)>,
+    V: Into<String>,
+{
+    HeaderResponder::new(header_name, templates, default)
+}
+
+impl HeaderResponder {
+    /// Specify the header to look at, the template to use for each of its values and the
+    /// template to fall back to.
+    ///
+    /// It panics if `header_name` is not a valid header name.
+    pub fn new<K, I, V>(header_name: K, templates: I, default: ResponseTemplate) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+        I: IntoIterator<Item = (V, ResponseTemplate)>,
+        V: Into<String>,
+    {
+        let header_name = header_name
+            .try_into()
+            .expect("Failed to convert into header name.");
+        let templates = templates
+            .into_iter()
+            .map(|(value, template)| (value.into(), template))
+            .collect();
+        Self {
+            header_name,
+            templates,
+            default,
+        }
+    }
+}
+
+impl Respond for HeaderResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        request
+            .headers
+            .get(&self.header_name)
+            .and_then(|values| {
+                values
+                    .iter()
+                    .find_map(|value| self.templates.get(value.as_str().trim()))
+            })
+            .unwrap_or(&self.default)
+            .clone()
+    }
+}
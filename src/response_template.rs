@@ -1,9 +1,14 @@
-use http_types::headers::{HeaderName, HeaderValue};
-use http_types::{Response, StatusCode};
+use crate::connection::{DeclaredContentLength, HeaderOrder, Throughput};
+use crate::{Error, Request};
+use async_std::prelude::*;
+use http_types::headers::{HeaderName, HeaderValue, CONTENT_LENGTH, TRANSFER_ENCODING};
+use http_types::{Response, StatusCode, Trailers};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// The blueprint for the response returned by a [`MockServer`] when a [`Mock`] matches on an incoming request.
@@ -14,9 +19,29 @@ use std::time::Duration;
 pub struct ResponseTemplate {
     mime: Option<http_types::Mime>,
     status_code: StatusCode,
-    headers: HashMap<HeaderName, Vec<HeaderValue>>,
+    // A `Vec` rather than a `HashMap`: headers are sent in the order they were first added.
+    headers: Vec<(HeaderName, Vec<HeaderValue>)>,
     body: Option<Vec<u8>>,
+    // Produces a fresh body for each response, if set. It replaces `body`.
+    body_generator: Option<BodyGenerator>,
     delay: Option<Duration>,
+    // Rate, in bytes per second, at which the body is written on the wire.
+    throughput: Option<u64>,
+    // If `true`, the `Range` header of the incoming request is honoured when serving `body`.
+    ranged: bool,
+    trailers: HashMap<HeaderName, Vec<HeaderValue>>,
+    // Deliberately wrong `Content-Length` to be sent instead of the actual body length.
+    declared_content_length: Option<usize>,
+}
+
+/// A cheaply cloneable handle to the closure passed to `set_body_generator`.
+#[derive(Clone)]
+struct BodyGenerator(Arc<dyn Fn() -> Vec<u8> + Send + Sync>);
+
+impl Debug for BodyGenerator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BodyGenerator")
+    }
 }
 
 // `wiremock` is a crate meant for testing - failures are most likely not handled/temporary mistakes.
@@ -29,47 +54,548 @@ pub struct ResponseTemplate {
 // Same principle applies to allocation/cloning, freely used where convenient.
 impl ResponseTemplate {
     /// Start building a `ResponseTemplate` specifying the status code of the response.
+    ///
+    /// It panics if `s` is not a valid status code - use [`try_new`](#method.try_new)
+    /// if you'd rather get an error back.
     pub fn new<S>(s: S) -> Self
     where
         S: TryInto<StatusCode>,
         <S as TryInto<StatusCode>>::Error: std::fmt::Debug,
     {
-        let status_code = s.try_into().expect("Failed to convert into status code.");
-        Self {
+        Self::try_new(s).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`new`](#method.new): it returns an [`Error`] instead of panicking
+    /// if `s` is not a valid status code.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{Error, ResponseTemplate};
+    ///
+    /// assert!(ResponseTemplate::try_new(200).is_ok());
+    /// assert!(matches!(
+    ///     ResponseTemplate::try_new(1000),
+    ///     Err(Error::InvalidStatusCode(_))
+    /// ));
+    /// ```
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_new<S>(s: S) -> Result<Self, Error>
+    where
+        S: TryInto<StatusCode>,
+        <S as TryInto<StatusCode>>::Error: std::fmt::Debug,
+    {
+        let status_code = s
+            .try_into()
+            .map_err(|e| Error::InvalidStatusCode(format!("{:?}", e)))?;
+        Ok(Self {
             status_code,
-            headers: HashMap::new(),
+            headers: Vec::new(),
             mime: None,
             body: None,
+            body_generator: None,
             delay: None,
+            throughput: None,
+            ranged: false,
+            trailers: HashMap::new(),
+            declared_content_length: None,
+        })
+    }
+
+    /// Build a `ResponseTemplate` replaying `response`: same status code, same headers and
+    /// same body.
+    ///
+    /// Use it to turn responses captured elsewhere (e.g. from a call to the real service)
+    /// into templates you can mount on a [`MockServer`] and tweak further with the usual
+    /// builder methods.
+    ///
+    /// `from_response` is an asynchronous method, make sure to `.await` it: the body of
+    /// `response` is read in full, and buffered, straight away.  
+    /// Framing is left to the [`MockServer`]: `Content-Length` and `Transfer-Encoding` are
+    /// not copied over, and neither are trailers.
+    ///
+    /// It panics if the body of `response` cannot be read.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use http_types::{Response, StatusCode};
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange - e.g. a response captured from the real service
+    ///     let mut captured = Response::new(StatusCode::Created);
+    ///     captured.insert_header("x-request-id", "42").unwrap();
+    ///     captured.set_body("hello");
+    ///
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(method("POST"))
+    ///         .respond_with(ResponseTemplate::from_response(captured).await)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut response = surf::post(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.status().as_u16(), 201);
+    ///     assert_eq!(response.header("x-request-id"), Some("42"));
+    ///     assert_eq!(response.body_string().await.unwrap(), "hello");
+    /// }
+    /// ```
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub async fn from_response(mut response: Response) -> Self {
+        let mut body = Vec::new();
+        response
+            .take_body()
+            .into_reader()
+            .read_to_end(&mut body)
+            .await
+            .expect("Failed to read body");
+
+        let mut template = Self::new(response.status());
+        // `http_types::Response` does not preserve the order of its headers: we sort them
+        // to get the same template every time.
+        let mut headers: Vec<(HeaderName, Vec<HeaderValue>)> = response
+            .iter()
+            .filter(|(name, _)| **name != CONTENT_LENGTH && **name != TRANSFER_ENCODING)
+            .map(|(name, values)| (name.clone(), values.clone()))
+            .collect();
+        headers.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        template.headers = headers;
+        if !body.is_empty() {
+            template.body = Some(body);
+        }
+        template
+    }
+
+    /// Start building a `ResponseTemplate` serving `body`, honouring the `Range` header
+    /// of the incoming request.
+    ///
+    /// - if the request has no `Range` header (or an invalid one), the whole body is returned
+    ///   with a `200 OK`;
+    /// - if the request asks for a satisfiable byte range (e.g. `bytes=0-99`, `bytes=100-` or
+    ///   `bytes=-100`), the requested slice of the body is returned with a
+    ///   `206 Partial Content` and the corresponding `Content-Range` header;
+    /// - if the requested byte range cannot be satisfied, a `416 Range Not Satisfiable` is
+    ///   returned with a `Content-Range: bytes */<length>` header.
+    ///
+    /// Requests with multiple ranges (e.g. `bytes=0-9,20-29`) are not supported: they get back
+    /// the whole body with a `200 OK`, as if they had not specified a `Range` header.
+    ///
+    /// All responses include an `Accept-Ranges: bytes` header.  
+    /// It sets "Content-Type" to "application/octet-stream".
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(ResponseTemplate::ranged_body("hello world"))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut response = surf::get(&mock_server.uri())
+    ///         .set_header("Range", "bytes=6-")
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.status().as_u16(), 206);
+    ///     assert_eq!(response.header("Content-Range"), Some("bytes 6-10/11"));
+    ///     assert_eq!(response.body_string().await.unwrap(), "world");
+    /// }
+    /// ```
+    pub fn ranged_body<B>(body: B) -> Self
+    where
+        B: TryInto<Vec<u8>>,
+        <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
+    {
+        let mut template = Self::new(200).set_body_bytes(body);
+        template.ranged = true;
+        template
+    }
+
+    /// Start building a `ResponseTemplate` answering a CORS preflight request.
+    ///
+    /// It returns a `204 No Content` with the following headers:
+    /// - `Access-Control-Allow-Origin`, set to `allowed_origin`;
+    /// - `Access-Control-Allow-Methods`, listing `allowed_methods`;
+    /// - `Access-Control-Allow-Headers`, listing `allowed_headers` (omitted if empty).
+    ///
+    /// Combine it with the [`is_cors_preflight`] matcher to mock the preflight requests
+    /// sent by browsers.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::is_cors_preflight;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let template = ResponseTemplate::cors_preflight(
+    ///         "https://example.com",
+    ///         vec!["GET", "POST"],
+    ///         vec!["Content-Type"],
+    ///     );
+    ///     Mock::given(is_cors_preflight())
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let res = surf::options(&mock_server.uri())
+    ///         .set_header("Origin", "https://example.com")
+    ///         .set_header("Access-Control-Request-Method", "POST")
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(res.status().as_u16(), 204);
+    ///     assert_eq!(res.header("Access-Control-Allow-Methods"), Some("GET, POST"));
+    /// }
+    /// ```
+    ///
+    /// [`is_cors_preflight`]: matchers/fn.is_cors_preflight.html
+    pub fn cors_preflight<O, M, H>(
+        allowed_origin: O,
+        allowed_methods: M,
+        allowed_headers: H,
+    ) -> Self
+    where
+        O: Into<String>,
+        M: IntoIterator,
+        M::Item: Into<String>,
+        H: IntoIterator,
+        H::Item: Into<String>,
+    {
+        let allowed_methods: Vec<String> = allowed_methods.into_iter().map(Into::into).collect();
+        let allowed_headers: Vec<String> = allowed_headers.into_iter().map(Into::into).collect();
+
+        let template = Self::new(204)
+            .insert_header(
+                "Access-Control-Allow-Origin",
+                allowed_origin.into().as_str(),
+            )
+            .insert_header(
+                "Access-Control-Allow-Methods",
+                allowed_methods.join(", ").as_str(),
+            );
+        if allowed_headers.is_empty() {
+            template
+        } else {
+            template.insert_header(
+                "Access-Control-Allow-Headers",
+                allowed_headers.join(", ").as_str(),
+            )
         }
     }
 
+    /// Start building a `ResponseTemplate` describing an error as an [RFC 7807] problem.
+    ///
+    /// It sets the status code to `s`, "Content-Type" to "application/problem+json"
+    /// and the body to a JSON object with the following fields:
+    /// - `type`, set to `"about:blank"` (i.e. the problem has no additional semantics beyond
+    ///   its status code);
+    /// - `title`, a short summary of the problem type;
+    /// - `status`, the status code as a number;
+    /// - `detail`, an explanation specific to this occurrence of the problem.
+    ///
+    /// Use [`set_body_raw`] if you need extension members or a custom `type`.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use serde_json::{json, Value};
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let template = ResponseTemplate::problem_json(
+    ///         403,
+    ///         "Insufficient credit",
+    ///         "Your current balance is 30, but that costs 50.",
+    ///     );
+    ///     Mock::given(method("POST"))
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut res = surf::post(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(res.status().as_u16(), 403);
+    ///     let body: Value = res.body_json().await.unwrap();
+    ///     assert_eq!(body["title"], json!("Insufficient credit"));
+    ///     assert_eq!(body["status"], json!(403));
+    /// }
+    /// ```
+    ///
+    /// [RFC 7807]: https://tools.ietf.org/html/rfc7807
+    /// [`set_body_raw`]: #method.set_body_raw
+    pub fn problem_json<S, T, D>(s: S, title: T, detail: D) -> Self
+    where
+        S: TryInto<StatusCode>,
+        <S as TryInto<StatusCode>>::Error: std::fmt::Debug,
+        T: Into<String>,
+        D: Into<String>,
+    {
+        let template = Self::new(s);
+        let problem = serde_json::json!({
+            "type": "about:blank",
+            "title": title.into(),
+            "status": u16::from(template.status_code),
+            "detail": detail.into(),
+        });
+        template.set_body_raw(problem.to_string(), "application/problem+json")
+    }
+
+    /// Start building a `ResponseTemplate` carrying a successful [JSON-RPC 2.0] response.
+    ///
+    /// It sets the status code to `200`, "Content-Type" to "application/json" and the body to
+    /// a JSON-RPC response object: `{"jsonrpc": "2.0", "result": <result>, "id": <id>}`.  
+    /// `id` must be the identifier of the request you are responding to (a number or a
+    /// string).
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use serde_json::{json, Value};
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::jsonrpc_method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(jsonrpc_method("get_balance"))
+    ///         .respond_with(ResponseTemplate::jsonrpc_result("req-1", json!({"balance": 30})))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let request = json!({"jsonrpc": "2.0", "method": "get_balance", "id": "req-1"});
+    ///     let mut res = surf::post(&mock_server.uri())
+    ///         .body_json(&request)
+    ///         .unwrap()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Assert
+    ///     let body: Value = res.body_json().await.unwrap();
+    ///     assert_eq!(body, json!({"jsonrpc": "2.0", "result": {"balance": 30}, "id": "req-1"}));
+    /// }
+    /// ```
+    ///
+    /// [JSON-RPC 2.0]: https://www.jsonrpc.org/specification
+    pub fn jsonrpc_result<I, R>(id: I, result: R) -> Self
+    where
+        I: Serialize,
+        R: Serialize,
+    {
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": result,
+            "id": id,
+        });
+        Self::new(200).set_body_json(response)
+    }
+
+    /// Start building a `ResponseTemplate` carrying a [JSON-RPC 2.0] error.
+    ///
+    /// It sets the status code to `200` (JSON-RPC errors travel in successful HTTP responses),
+    /// "Content-Type" to "application/json" and the body to a JSON-RPC response object:
+    /// `{"jsonrpc": "2.0", "error": {"code": <code>, "message": <message>}, "id": <id>}`.  
+    /// Use the codes reserved by the specification (e.g. `-32601` for "Method not found") or
+    /// your own application-defined ones.  
+    /// Pass `serde_json::Value::Null` as `id` for errors that occurred before the id of the
+    /// request could be determined (e.g. `-32700`, "Parse error").
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use serde_json::{json, Value};
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::jsonrpc_method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     Mock::given(jsonrpc_method("foobar"))
+    ///         .respond_with(ResponseTemplate::jsonrpc_error(1, -32601, "Method not found"))
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let request = json!({"jsonrpc": "2.0", "method": "foobar", "id": 1});
+    ///     let mut res = surf::post(&mock_server.uri())
+    ///         .body_json(&request)
+    ///         .unwrap()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(res.status().as_u16(), 200);
+    ///     let body: Value = res.body_json().await.unwrap();
+    ///     assert_eq!(body["error"]["code"], json!(-32601));
+    /// }
+    /// ```
+    ///
+    /// [JSON-RPC 2.0]: https://www.jsonrpc.org/specification
+    pub fn jsonrpc_error<I, M>(id: I, code: i64, message: M) -> Self
+    where
+        I: Serialize,
+        M: Into<String>,
+    {
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": code,
+                "message": message.into(),
+            },
+            "id": id,
+        });
+        Self::new(200).set_body_json(response)
+    }
+
     /// Append a header `value` to list of headers with `key` as header name.
     ///
     /// Unlike `insert_header`, this function will not override the contents of a header:
     /// - if there are no header values with `key` as header name, it will insert one;
     /// - if there are already some values with `key` as header name, it will append to the
     ///   existing list.
-    pub fn append_header<K, V>(mut self, key: K, value: V) -> Self
+    ///
+    /// It panics if `key` or `value` are not valid - use
+    /// [`try_append_header`](#method.try_append_header) if you'd rather get an error back.
+    pub fn append_header<K, V>(self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.try_append_header(key, value)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`append_header`](#method.append_header): it returns an [`Error`]
+    /// instead of panicking if `key` or `value` are not valid.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{Error, ResponseTemplate};
+    ///
+    /// let template = ResponseTemplate::new(200).try_append_header("x-valid", "value");
+    /// assert!(template.is_ok());
+    ///
+    /// // Header values must be ASCII
+    /// let template = ResponseTemplate::new(200).try_append_header("x-invalid", "välue");
+    /// assert!(matches!(template, Err(Error::InvalidHeaderValue(_))));
+    /// ```
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_append_header<K, V>(mut self, key: K, value: V) -> Result<Self, Error>
     where
         K: TryInto<HeaderName>,
         <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
         V: TryInto<HeaderValue>,
         <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
     {
-        let key = key.try_into().expect("Failed to convert into header name.");
+        let key = key
+            .try_into()
+            .map_err(|e| Error::InvalidHeaderName(format!("{:?}", e)))?;
         let value = value
             .try_into()
-            .expect("Failed to convert into header value.");
-        match self.headers.get_mut(&key) {
-            Some(headers) => {
-                headers.push(value);
+            .map_err(|e| Error::InvalidHeaderValue(format!("{:?}", e)))?;
+        match self.headers.iter_mut().find(|(name, _)| name == &key) {
+            Some((_, values)) => {
+                values.push(value);
             }
             None => {
-                self.headers.insert(key, vec![value]);
+                self.headers.push((key, vec![value]));
             }
         }
-        self
+        Ok(self)
+    }
+
+    /// Append a trailer `value` to the list of trailers with `key` as name.
+    ///
+    /// Trailers are headers sent after the body of the response (e.g. `grpc-status` for
+    /// gRPC-web).  
+    /// They can only be sent using chunked transfer encoding: if at least one trailer is
+    /// specified, the response is sent with `Transfer-Encoding: chunked` (instead of
+    /// `Content-Length`) and a `Trailer` header listing the names of all trailers.
+    ///
+    /// It panics if `key` or `value` are not valid - use
+    /// [`try_append_trailer`](#method.try_append_trailer) if you'd rather get an error back.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let template = ResponseTemplate::new(200)
+    ///         .set_body_bytes("payload")
+    ///         .append_trailer("grpc-status", "0");
+    ///     Mock::given(method("POST"))
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut res = surf::post(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(res.header("trailer"), Some("grpc-status"));
+    ///     assert_eq!(res.body_string().await.unwrap(), "payload");
+    /// }
+    /// ```
+    pub fn append_trailer<K, V>(self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.try_append_trailer(key, value)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`append_trailer`](#method.append_trailer): it returns an [`Error`]
+    /// instead of panicking if `key` or `value` are not valid.
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_append_trailer<K, V>(mut self, key: K, value: V) -> Result<Self, Error>
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        let key = key
+            .try_into()
+            .map_err(|e| Error::InvalidHeaderName(format!("{:?}", e)))?;
+        let value = value
+            .try_into()
+            .map_err(|e| Error::InvalidHeaderValue(format!("{:?}", e)))?;
+        self.trailers.entry(key).or_default().push(value);
+        Ok(self)
     }
 
     /// Insert a header `value` with `key` as header name.
@@ -78,19 +604,43 @@ impl ResponseTemplate {
     /// - if there are no header values with `key` as header name, it will insert one;
     /// - if there are already some values with `key` as header name, it will drop them and
     ///   start a new list of header values, containing only `value`.
-    pub fn insert_header<K, V>(mut self, key: K, value: V) -> Self
+    ///
+    /// It panics if `key` or `value` are not valid - use
+    /// [`try_insert_header`](#method.try_insert_header) if you'd rather get an error back.
+    pub fn insert_header<K, V>(self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.try_insert_header(key, value)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`insert_header`](#method.insert_header): it returns an [`Error`]
+    /// instead of panicking if `key` or `value` are not valid.
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_insert_header<K, V>(mut self, key: K, value: V) -> Result<Self, Error>
     where
         K: TryInto<HeaderName>,
         <K as TryInto<HeaderName>>::Error: std::fmt::Debug,
         V: TryInto<HeaderValue>,
         <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
     {
-        let key = key.try_into().expect("Failed to convert into header name.");
+        let key = key
+            .try_into()
+            .map_err(|e| Error::InvalidHeaderName(format!("{:?}", e)))?;
         let value = value
             .try_into()
-            .expect("Failed to convert into header value.");
-        self.headers.insert(key, vec![value]);
-        self
+            .map_err(|e| Error::InvalidHeaderValue(format!("{:?}", e)))?;
+        // An existing header keeps its position.
+        match self.headers.iter_mut().find(|(name, _)| name == &key) {
+            Some((_, values)) => *values = vec![value],
+            None => self.headers.push((key, vec![value])),
+        }
+        Ok(self)
     }
 
     /// Set the response body with bytes.
@@ -104,6 +654,7 @@ impl ResponseTemplate {
     {
         let body = body.try_into().expect("Failed to convert into body.");
         self.body = Some(body);
+        self.body_generator = None;
         self
     }
 
@@ -113,50 +664,105 @@ impl ResponseTemplate {
     ///
     /// To set a body with bytes but a different "Content-Type"
     /// [`set_body_raw`](#method.set_body_raw) can be used.
-    pub fn set_body_bytes<B>(mut self, body: B) -> Self
+    ///
+    /// It panics if `body` cannot be converted into bytes - use
+    /// [`try_set_body_bytes`](#method.try_set_body_bytes) if you'd rather get an error back.
+    pub fn set_body_bytes<B>(self, body: B) -> Self
     where
         B: TryInto<Vec<u8>>,
         <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
     {
-        let body = body.try_into().expect("Failed to convert into body.");
+        self.try_set_body_bytes(body)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`set_body_bytes`](#method.set_body_bytes): it returns an [`Error`]
+    /// instead of panicking if `body` cannot be converted into bytes.
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_set_body_bytes<B>(mut self, body: B) -> Result<Self, Error>
+    where
+        B: TryInto<Vec<u8>>,
+        <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
+    {
+        let body = body
+            .try_into()
+            .map_err(|e| Error::InvalidBody(format!("{:?}", e)))?;
         self.body = Some(body);
-        self
+        self.body_generator = None;
+        Ok(self)
     }
 
     /// Set the response body from a JSON-serializable value.
     ///
     /// It sets "Content-Type" to "application/json".
-    pub fn set_body_json<B: Serialize>(mut self, body: B) -> Self {
-        let body = serde_json::to_vec(&body).expect("Failed to convert into body.");
+    ///
+    /// It panics if `body` cannot be serialized - use
+    /// [`try_set_body_json`](#method.try_set_body_json) if you'd rather get an error back.
+    pub fn set_body_json<B: Serialize>(self, body: B) -> Self {
+        self.try_set_body_json(body)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`set_body_json`](#method.set_body_json): it returns an [`Error`]
+    /// instead of panicking if `body` cannot be serialized as JSON.
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_set_body_json<B: Serialize>(mut self, body: B) -> Result<Self, Error> {
+        let body =
+            serde_json::to_vec(&body).map_err(|e| Error::BodySerialization(e.to_string()))?;
 
         self.body = Some(body);
+        self.body_generator = None;
         self.mime = Some(
             http_types::Mime::from_str("application/json")
                 .expect("Failed to convert into Mime header"),
         );
-        self
+        Ok(self)
     }
 
     /// Set the response body to a string.
     ///
     /// It sets "Content-Type" to "text/plain".
-    pub fn set_body_string<T>(mut self, body: T) -> Self
+    ///
+    /// It panics if `body` cannot be converted into a string - use
+    /// [`try_set_body_string`](#method.try_set_body_string) if you'd rather get an error back.
+    pub fn set_body_string<T>(self, body: T) -> Self
     where
         T: TryInto<String>,
         <T as TryInto<String>>::Error: std::fmt::Debug,
     {
-        let body = body.try_into().expect("Failed to convert into body.");
+        self.try_set_body_string(body)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`set_body_string`](#method.set_body_string): it returns an
+    /// [`Error`] instead of panicking if `body` cannot be converted into a string.
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_set_body_string<T>(mut self, body: T) -> Result<Self, Error>
+    where
+        T: TryInto<String>,
+        <T as TryInto<String>>::Error: std::fmt::Debug,
+    {
+        let body = body
+            .try_into()
+            .map_err(|e| Error::InvalidBody(format!("{:?}", e)))?;
 
         self.body = Some(body.into_bytes());
+        self.body_generator = None;
         self.mime = Some(
             http_types::Mime::from_str("text/plain").expect("Failed to convert into Mime header"),
         );
-        self
+        Ok(self)
     }
 
     /// Set a raw response body. The mime type needs to be set because the
     /// raw body could be of any type.
     ///
+    /// It panics if `body` cannot be converted into bytes or `mime` is not a valid mime type -
+    /// use [`try_set_body_raw`](#method.try_set_body_raw) if you'd rather get an error back.
+    ///
     /// ### Example:
     /// ```rust
     /// use surf::mime;
@@ -198,18 +804,183 @@ impl ResponseTemplate {
     ///     assert_eq!(res.mime(), Some(mime::APPLICATION_JSON));
     /// }
     /// ```
-    pub fn set_body_raw<B>(mut self, body: B, mime: &str) -> Self
+    pub fn set_body_raw<B>(self, body: B, mime: &str) -> Self
     where
         B: TryInto<Vec<u8>>,
         <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
     {
-        let body = body.try_into().expect("Failed to convert into body.");
+        self.try_set_body_raw(body, mime)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of [`set_body_raw`](#method.set_body_raw): it returns an [`Error`]
+    /// instead of panicking if `body` cannot be converted into bytes or `mime` is not a
+    /// valid "Content-Type".
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_set_body_raw<B>(mut self, body: B, mime: &str) -> Result<Self, Error>
+    where
+        B: TryInto<Vec<u8>>,
+        <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
+    {
+        let body = body
+            .try_into()
+            .map_err(|e| Error::InvalidBody(format!("{:?}", e)))?;
+        let mime = http_types::Mime::from_str(mime)
+            .map_err(|e| Error::InvalidHeaderValue(format!("{:?}", e)))?;
         self.body = Some(body);
-        self.mime =
-            Some(http_types::Mime::from_str(mime).expect("Failed to convert into Mime header"));
+        self.body_generator = None;
+        self.mime = Some(mime);
+        Ok(self)
+    }
+
+    /// Set the response body to `body` compressed with Brotli, adding a
+    /// `Content-Encoding: br` header.
+    ///
+    /// Use it to exercise the decompression path of your client.  
+    /// The body is compressed once, when the template is built. "Content-Type" is not set:
+    /// use [`insert_header`] to describe the uncompressed content.
+    ///
+    /// It is only available if the `brotli` feature is enabled.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let template = ResponseTemplate::new(200)
+    ///         .set_body_brotli(r#"{"hello": "world"}"#)
+    ///         .insert_header("content-type", "application/json");
+    ///     Mock::given(method("HEAD"))
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act - a `HEAD` request, our HTTP client cannot decode Brotli
+    ///     let response = surf::head(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.header("content-encoding"), Some("br"));
+    /// }
+    /// ```
+    ///
+    /// [`insert_header`]: #method.insert_header
+    #[cfg(feature = "brotli")]
+    pub fn set_body_brotli<B>(self, body: B) -> Self
+    where
+        B: TryInto<Vec<u8>>,
+        <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
+    {
+        use std::io::Write;
+
+        let body = body.try_into().expect("Failed to convert into body.");
+        // Quality 5 and a 4MB window: a reasonable trade-off between speed and ratio,
+        // the same defaults as most web servers.
+        let mut compressor = brotli_compressor::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        compressor
+            .write_all(&body)
+            .expect("Failed to compress the body.");
+        let compressed = compressor.into_inner();
+        self.set_body_bytes(compressed)
+            .insert_header("content-encoding", "br")
+    }
+
+    /// Generate the response body using `generator`, invoked once for every response
+    /// built from this template.
+    ///
+    /// Use it for large or randomised payloads: unlike [`set_body_bytes`], the body does
+    /// not live in the template (nor in every `Mock` or responder holding a copy of it) and
+    /// it can differ from one request to the next.  
+    /// Each generated body is held in memory while its own response is being sent: it is not
+    /// streamed to the client, but it is dropped as soon as the response has been written.
+    ///
+    /// It replaces any body set before with the other `set_body_*` methods (and vice versa).
+    /// "Content-Type" is not set: use [`insert_header`] if you need one.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     // 8 MB of data, allocated only when a request comes in.
+    ///     let template = ResponseTemplate::new(200).set_body_generator(|| vec![42; 8 * 1024 * 1024]);
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let mut response = surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     let body = response.body_bytes().await.unwrap();
+    ///     assert_eq!(body.len(), 8 * 1024 * 1024);
+    /// }
+    /// ```
+    ///
+    /// [`set_body_bytes`]: #method.set_body_bytes
+    /// [`insert_header`]: #method.insert_header
+    pub fn set_body_generator<F>(mut self, generator: F) -> Self
+    where
+        F: Fn() -> Vec<u8> + Send + Sync + 'static,
+    {
+        self.body = None;
+        self.body_generator = Some(BodyGenerator(Arc::new(generator)));
         self
     }
 
+    /// Set the response body to `actual_body`, but declare a `Content-Length` of `declared`
+    /// bytes.
+    ///
+    /// **This produces invalid HTTP on purpose**: use it to check how your client copes with
+    /// broken framing (e.g. a truncated body or trailing garbage).
+    ///
+    /// The connection is closed right after the response has been written: a client waiting
+    /// for more bytes than `actual_body` gets an unexpected EOF, while a client reading
+    /// less bytes than `actual_body` finds the leftovers where it expects the next response.  
+    /// The response is always framed using `Content-Length`: trailers, if any, are not sent.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     // We promise 100 bytes, but we only send 5.
+    ///     let template = ResponseTemplate::new(200).set_corrupt_content_length(100, "hello");
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let response = surf::get(&mock_server.uri()).await.unwrap();
+    ///
+    ///     // Assert
+    ///     assert_eq!(response.header("content-length"), Some("100"));
+    /// }
+    /// ```
+    pub fn set_corrupt_content_length<B>(self, declared: usize, actual_body: B) -> Self
+    where
+        B: TryInto<Vec<u8>>,
+        <B as TryInto<Vec<u8>>>::Error: std::fmt::Debug,
+    {
+        let mut template = self.set_body_bytes(actual_body);
+        template.declared_content_length = Some(declared);
+        template
+    }
+
     /// By default the [`MockServer`] tries to fulfill incoming requests as fast as possible.
     ///
     /// You can use `set_delay` to introduce an artificial delay to simulate the behaviour of
@@ -252,8 +1023,62 @@ impl ResponseTemplate {
         self
     }
 
-    /// Generate a response from the template.
-    pub(crate) fn generate_response(&self) -> Response {
+    /// Write the body of the response at `bytes_per_sec`, to simulate a server behind a slow
+    /// network link.
+    ///
+    /// The head of the response is sent straight away, while the body trickles in over
+    /// (roughly) `body length / bytes_per_sec` seconds: you can use it to test progress
+    /// reporting or read timeouts in your client.  
+    /// Combine it with [`set_delay`] to model both latency and bandwidth.
+    ///
+    /// The connection is kept busy until the whole body has been written: a client reusing
+    /// it has to wait for the transfer to complete before its next request is served.
+    ///
+    /// It panics if `bytes_per_sec` is 0.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use wiremock::{MockServer, Mock, ResponseTemplate};
+    /// use wiremock::matchers::method;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     // Arrange
+    ///     let mock_server = MockServer::start().await;
+    ///     let template = ResponseTemplate::new(200)
+    ///         .set_body_bytes(vec![b'a'; 1_000])
+    ///         .set_throughput(4_000);
+    ///     Mock::given(method("GET"))
+    ///         .respond_with(template)
+    ///         .mount(&mock_server)
+    ///         .await;
+    ///
+    ///     // Act
+    ///     let start = Instant::now();
+    ///     let body = surf::get(&mock_server.uri())
+    ///         .await
+    ///         .unwrap()
+    ///         .body_bytes()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     // Assert - 1000 bytes at 4000 bytes per second take at least a quarter of a second
+    ///     assert_eq!(body.len(), 1_000);
+    ///     assert!(start.elapsed() >= Duration::from_millis(200));
+    /// }
+    /// ```
+    ///
+    /// [`set_delay`]: #method.set_delay
+    pub fn set_throughput(mut self, bytes_per_sec: u64) -> Self {
+        assert!(bytes_per_sec > 0, "The throughput must be greater than 0.");
+        self.throughput = Some(bytes_per_sec);
+
+        self
+    }
+
+    /// Generate a response from the template for `request`.
+    pub(crate) fn generate_response(&self, request: &Request) -> Response {
         let mut response = Response::new(self.status_code);
 
         // Add headers
@@ -262,10 +1087,23 @@ impl ResponseTemplate {
                 .insert_header(header_name.clone(), header_values.as_slice())
                 .unwrap();
         }
+        // `http_types::Response` does not preserve the order of its headers.
+        if !self.headers.is_empty() {
+            let order = self.headers.iter().map(|(name, _)| name.clone()).collect();
+            response.local_mut().insert(HeaderOrder(order));
+        }
 
         // Add body, if specified
-        if let Some(body) = &self.body {
-            response.set_body(body.clone());
+        let body = match &self.body_generator {
+            Some(generator) => Some((generator.0)()),
+            None => self.body.clone(),
+        };
+        if let Some(body) = body {
+            if self.ranged {
+                set_ranged_body(&mut response, &body, request);
+            } else {
+                response.set_body(body);
+            }
         }
 
         // Set content-type, if needed
@@ -273,6 +1111,22 @@ impl ResponseTemplate {
             response.set_content_type(mime.to_owned());
         }
 
+        // The connection layer picks up wire-level directives from the local state of
+        // the response.
+        if let Some(declared) = self.declared_content_length {
+            response.local_mut().insert(DeclaredContentLength(declared));
+        }
+        if !self.trailers.is_empty() {
+            let mut trailers = Trailers::new();
+            for (name, values) in &self.trailers {
+                trailers.append(name.clone(), values.as_slice()).unwrap();
+            }
+            response.local_mut().insert(trailers);
+        }
+        if let Some(bytes_per_sec) = self.throughput {
+            response.local_mut().insert(Throughput(bytes_per_sec));
+        }
+
         response
     }
 
@@ -281,3 +1135,76 @@ impl ResponseTemplate {
         &self.delay
     }
 }
+
+/// Set the slice of `body` requested via the `Range` header of `request`, adjusting the
+/// status code and the range-related headers of `response`.
+fn set_ranged_body(response: &mut Response, body: &[u8], request: &Request) {
+    let header_name: HeaderName = "Range".try_into().unwrap();
+    let range = request
+        .headers
+        .get(&header_name)
+        .and_then(|values| values.last())
+        .and_then(|value| parse_byte_range(value.as_str(), body.len()));
+
+    response.insert_header("Accept-Ranges", "bytes").unwrap();
+    match range {
+        None => {
+            response.set_body(body.to_vec());
+        }
+        Some(Ok((start, end))) => {
+            response.set_status(StatusCode::PartialContent);
+            response
+                .insert_header(
+                    "Content-Range",
+                    format!("bytes {}-{}/{}", start, end, body.len()).as_str(),
+                )
+                .unwrap();
+            response.set_body(body[start..=end].to_vec());
+        }
+        Some(Err(())) => {
+            response.set_status(StatusCode::RequestedRangeNotSatisfiable);
+            response
+                .insert_header("Content-Range", format!("bytes */{}", body.len()).as_str())
+                .unwrap();
+        }
+    }
+}
+
+/// Parse the value of a `Range` header for a body of `len` bytes.
+///
+/// It returns:
+/// - `None` if the header should be ignored (invalid syntax, unsupported unit or multiple ranges);
+/// - `Some(Ok((start, end)))` for a satisfiable range (`end` is inclusive);
+/// - `Some(Err(()))` for an unsatisfiable range.
+fn parse_byte_range(value: &str, len: usize) -> Option<Result<(usize, usize), ()>> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_at(spec.find('-')?);
+    let (start, end) = (start.trim(), end[1..].trim());
+
+    let range = if start.is_empty() {
+        // Suffix range, e.g. `bytes=-100` for the last 100 bytes.
+        let suffix_len: usize = end.parse().ok()?;
+        if suffix_len == 0 || len == 0 {
+            Err(())
+        } else {
+            Ok((len.saturating_sub(suffix_len), len - 1))
+        }
+    } else {
+        let start: usize = start.parse().ok()?;
+        let end: Option<usize> = if end.is_empty() {
+            None
+        } else {
+            Some(end.parse().ok()?)
+        };
+        match end {
+            Some(end) if end < start => return None,
+            _ if start >= len => Err(()),
+            Some(end) => Ok((start, end.min(len - 1))),
+            None => Ok((start, len - 1)),
+        }
+    };
+    Some(range)
+}
@@ -1,39 +1,55 @@
-use crate::mock_actor::MockActor;
+use crate::connection::{self, ConnectionSettings};
+use crate::error::Error;
+use crate::liveness::Liveness;
+use crate::mock_actor::{IncomingRequest, MockActor};
 use async_std::net::TcpListener;
-use async_std::prelude::*;
 use bastion::prelude::*;
+use futures::StreamExt;
 use http_types::{Response, StatusCode};
 use log::{debug, info, warn};
+use socket2::{Domain, Protocol, Socket, Type};
 use std::net::SocketAddr;
 
 #[derive(Clone)]
 pub(crate) struct ServerActor {
     pub(crate) actor_ref: ChildRef,
     pub(crate) address: SocketAddr,
+    // Tracks the accept loop.
+    pub(crate) liveness: Liveness,
 }
 
 impl ServerActor {
-    pub(crate) async fn start(mock_actor: MockActor) -> ServerActor {
-        // Allocate a random port
-        let listener = get_available_port()
-            .await
-            .expect("No free port - cannot start an HTTP mock server!");
-        Self::start_with(listener, mock_actor)
-    }
-
-    pub(crate) fn start_with(listener: TcpListener, mock_actor: MockActor) -> ServerActor {
+    /// Start serving the connections received by `listener` (see [`get_available_port`]).
+    ///
+    /// `accept_concurrency` caps the number of connections served in parallel (unbounded
+    /// if `None`), while `settings` applies to each of them.
+    ///
+    /// [`get_available_port`]: fn.get_available_port.html
+    pub(crate) fn start_with(
+        listener: TcpListener,
+        mock_actor: MockActor,
+        accept_concurrency: Option<usize>,
+        settings: ConnectionSettings,
+    ) -> ServerActor {
         let address = listener.local_addr().unwrap();
+        let liveness = Liveness::new();
+        let actor_liveness = liveness.clone();
 
         let server_actors = Bastion::children(|children: Children| {
             children
                 .with_exec(move |ctx: BastionContext| {
+                    let liveness = actor_liveness.clone();
                     async move {
                         loop {
                             msg! { ctx.recv().await?,
-                                msg: (ChildRef, TcpListener) => {
-                                    let (mock_actor, listener) = msg;
+                                msg: (ChildRef, TcpListener, Option<usize>, ConnectionSettings) => {
+                                    let (mock_actor, listener, accept_concurrency, settings) = msg;
                                     debug!("Mock server started listening on {}!", listener.local_addr().unwrap());
-                                    async_std::task::spawn(listen(mock_actor, listener)).await;
+                                    let liveness_guard = liveness.guard();
+                                    async_std::task::spawn(async move {
+                                        let _liveness_guard = liveness_guard;
+                                        listen(mock_actor, listener, accept_concurrency, settings).await
+                                    }).await;
                                     debug!("Shutting down!");
                                 };
                                 _: _ => {
@@ -51,29 +67,44 @@ impl ServerActor {
         // Pass in the TcpListener to start receiving connections and the mock actor
         // ChildRef to know what to respond to requests
         server_actor
-            .tell_anonymously((mock_actor.actor_ref, listener))
+            .tell_anonymously((mock_actor.actor_ref, listener, accept_concurrency, settings))
             .expect("Failed to post TcpListener and mock actor address.");
 
         ServerActor {
             actor_ref: server_actor,
             address,
+            liveness,
         }
     }
 }
 
-async fn listen(mock_actor: ChildRef, listener: async_std::net::TcpListener) {
+async fn listen(
+    mock_actor: ChildRef,
+    listener: async_std::net::TcpListener,
+    accept_concurrency: Option<usize>,
+    settings: ConnectionSettings,
+) {
     let addr = format!("http://{}", listener.local_addr().unwrap());
-    while let Some(stream) = listener.incoming().next().await {
-        // For each incoming stream, spawn up a task.
-        let stream = stream.unwrap();
-        let addr = addr.clone();
-        let actor = mock_actor.clone();
-        async_std::task::spawn(async {
-            if let Err(err) = accept(actor, addr, stream).await {
-                warn!("{}", err);
+    listener
+        .incoming()
+        .for_each_concurrent(accept_concurrency, |stream| {
+            let stream = stream.unwrap();
+            let addr = addr.clone();
+            let actor = mock_actor.clone();
+            let settings = settings.clone();
+            async move {
+                // For each incoming stream, spawn up a task: it gets the connection served
+                // in parallel, not only concurrently.
+                // We wait for it to complete to enforce `accept_concurrency`.
+                async_std::task::spawn(async move {
+                    if let Err(err) = accept(actor, addr, stream, &settings).await {
+                        warn!("{}", err);
+                    }
+                })
+                .await;
             }
-        });
-    }
+        })
+        .await;
 }
 
 // Take a TCP stream, and convert it into sequential HTTP request / response pairs.
@@ -81,20 +112,31 @@ async fn accept(
     mock_actor: ChildRef,
     addr: String,
     stream: async_std::net::TcpStream,
+    settings: &ConnectionSettings,
 ) -> http_types::Result<()> {
     debug!("Starting new connection from {}", stream.peer_addr()?);
-    async_h1::accept(&addr, stream.clone(), move |req| {
+    connection::accept(&addr, stream, settings, move |req, span| {
         let a = mock_actor.clone();
         async move {
             info!("Request: {:?}", req);
-            let answer = (&a).ask_anonymously(req).unwrap();
-
-            let response = msg! { answer.await.expect("Couldn't receive the answer."),
-                msg: Response => msg;
-                _: _ => Response::new(StatusCode::NotFound);
+            // If the mock actor died (e.g. a custom matcher panicked) we won't get an answer:
+            // we return a 500 instead of leaving the client hanging.
+            let answer = match a.ask_anonymously(IncomingRequest { request: req, span }) {
+                Ok(answer) => answer.await.ok(),
+                Err(_) => None,
+            };
+            let response = match answer {
+                Some(answer) => msg! { answer,
+                    msg: Response => msg;
+                    _: _ => Response::new(StatusCode::NotFound);
+                },
+                None => {
+                    warn!("The mock actor is not running anymore.");
+                    Response::new(StatusCode::InternalServerError)
+                }
             };
             info!("Response: {:?}", response);
-            Ok(response)
+            response
         }
     })
     .await?;
@@ -102,14 +144,39 @@ async fn accept(
 }
 
 /// Get a local TCP listener for an available port.
-/// If no port is available, returns None.
-async fn get_available_port() -> Option<TcpListener> {
+/// If no port is available, returns `Error::BindFailed` with the error we got binding
+/// the last port we tried.
+///
+/// `listen_backlog` overrides the default size of the listen backlog of the socket.
+pub(crate) async fn get_available_port(listen_backlog: Option<u32>) -> Result<TcpListener, Error> {
+    let mut last_error = None;
     for port in 8000..9000 {
         // Check if the specified port if available.
-        match TcpListener::bind(("127.0.0.1", port)).await {
-            Ok(l) => return Some(l),
-            Err(_) => continue,
+        let listener = match listen_backlog {
+            Some(backlog) => bind_with_backlog(([127, 0, 0, 1], port).into(), backlog),
+            None => TcpListener::bind(("127.0.0.1", port)).await,
+        };
+        match listener {
+            Ok(l) => return Ok(l),
+            Err(e) => last_error = Some(e),
         }
     }
-    None
+    Err(Error::BindFailed(
+        last_error.expect("We tried at least one port."),
+    ))
+}
+
+/// Bind a TCP listener to `address` using a listen backlog of size `backlog`.
+///
+/// The standard library does not let us customise the backlog: we need to set up the
+/// socket by hand, mirroring what `std::net::TcpListener::bind` does.
+fn bind_with_backlog(address: SocketAddr, backlog: u32) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::ipv4(), Type::stream(), Some(Protocol::tcp()))?;
+    // On platforms with Berkeley-derived sockets, this allows to quickly rebind a socket,
+    // without needing to wait for the OS to clean up the previous one.
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.bind(&address.into())?;
+    socket.listen(backlog.min(i32::MAX as u32) as i32)?;
+    Ok(socket.into_tcp_listener().into())
 }
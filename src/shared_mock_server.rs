@@ -0,0 +1,195 @@
+use crate::MockServer;
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+/// A cheaply cloneable handle to a [`MockServer`], to share a single instance between
+/// many tests.
+///
+/// Starting a [`MockServer`] for each test gives you full isolation, but it adds up in large
+/// test suites hitting the same mocks over and over.  
+/// `SharedMockServer` is reference counted: every clone points to the same [`MockServer`]
+/// and dereferences to it - you can call any [`MockServer`] method on it.  
+/// The [`MockServer`] is verified and shut down when the last clone is dropped.
+///
+/// Use a [`LazyMockServer`] to share an instance between all the tests in a module.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{Mock, ResponseTemplate, SharedMockServer};
+/// use wiremock::matchers::method;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange
+///     let mock_server = SharedMockServer::start().await;
+///     let handle = mock_server.clone();
+///     Mock::given(method("GET"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&handle)
+///         .await;
+///
+///     // Act
+///     let status = surf::get(&mock_server.uri())
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+///     assert_eq!(mock_server.address(), handle.address());
+/// }
+/// ```
+///
+/// [`MockServer`]: struct.MockServer.html
+/// [`LazyMockServer`]: struct.LazyMockServer.html
+#[derive(Clone)]
+pub struct SharedMockServer(Arc<MockServer>);
+
+impl SharedMockServer {
+    /// Start a new instance of a [`MockServer`] and wrap it in a `SharedMockServer`.
+    ///
+    /// It panics if the [`MockServer`] cannot be started.
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub async fn start() -> Self {
+        MockServer::start().await.into()
+    }
+}
+
+impl From<MockServer> for SharedMockServer {
+    fn from(server: MockServer) -> Self {
+        Self(Arc::new(server))
+    }
+}
+
+impl Deref for SharedMockServer {
+    type Target = MockServer;
+
+    fn deref(&self) -> &MockServer {
+        &self.0
+    }
+}
+
+impl Debug for SharedMockServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedMockServer")
+            .field(self.0.address())
+            .finish()
+    }
+}
+
+/// A [`SharedMockServer`] started the first time it is needed, meant to be stored in a
+/// `static` and shared by all the tests in a module (or in a test binary).
+///
+/// [`get`] starts the [`MockServer`] on the first call and hands out a clone of the same
+/// [`SharedMockServer`] afterwards.  
+/// A `static` is never dropped: the [`MockServer`] keeps running until the test binary
+/// exits. It is **not** registered with any [`TestScope`], even if the first call to [`get`]
+/// happens within one.
+///
+/// ### Discipline
+///
+/// Tests sharing a [`MockServer`] can see each other's mocks and requests. Cargo runs the
+/// tests of a binary in parallel by default:
+/// - mount the mocks of each test with [`register_scoped`]. They are removed, and their
+///   expectations verified, when the returned [`MockGuard`] goes out of scope at the end of
+///   the test - mocks mounted with [`mount`] are never verified on a shared instance;
+/// - give each test its own paths (or whatever your matchers key on): the mocks of a test
+///   must not match the requests of another;
+/// - do not assert on server-wide state ([`total_requests`], [`received_requests`], ...)
+///   nor [`reset`] the server, unless the tests sharing it run one at a time
+///   (`cargo test -- --test-threads=1`). If they do, call [`reset`] at the beginning of
+///   each test to start from a clean slate.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{LazyMockServer, Mock, ResponseTemplate};
+/// use wiremock::matchers::path;
+///
+/// static MOCK_SERVER: LazyMockServer = LazyMockServer::new();
+///
+/// #[async_std::main]
+/// async fn main() {
+///     // Arrange - the first test to get here starts the server, the others reuse it
+///     let mock_server = MOCK_SERVER.get().await;
+///     let _guard = mock_server
+///         .register_scoped(
+///             Mock::given(path("/my-test"))
+///                 .respond_with(ResponseTemplate::new(200))
+///                 .expect(1),
+///         )
+///         .await;
+///
+///     // Act
+///     let status = surf::get(&mock_server.uri_for("/my-test"))
+///         .await
+///         .unwrap()
+///         .status();
+///
+///     // Assert
+///     assert_eq!(status.as_u16(), 200);
+///     assert_eq!(MOCK_SERVER.get().await.address(), mock_server.address());
+/// }
+/// ```
+///
+/// [`SharedMockServer`]: struct.SharedMockServer.html
+/// [`MockServer`]: struct.MockServer.html
+/// [`TestScope`]: struct.TestScope.html
+/// [`MockGuard`]: struct.MockGuard.html
+/// [`get`]: #method.get
+/// [`register_scoped`]: struct.MockServer.html#method.register_scoped
+/// [`mount`]: struct.Mock.html#method.mount
+/// [`total_requests`]: struct.MockServer.html#method.total_requests
+/// [`received_requests`]: struct.MockServer.html#method.received_requests
+/// [`reset`]: struct.MockServer.html#method.reset
+pub struct LazyMockServer(Mutex<Option<SharedMockServer>>);
+
+impl LazyMockServer {
+    /// Create an empty `LazyMockServer`: no [`MockServer`] is started until [`get`] is called.
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    /// [`get`]: #method.get
+    pub const fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Get a handle to the shared [`MockServer`], starting it if this is the first call.
+    ///
+    /// If two tests call `get` at the same time before the [`MockServer`] has been started
+    /// they might both start one: only the first to finish is kept, the other one is shut
+    /// down right away.
+    ///
+    /// It panics if the [`MockServer`] cannot be started.
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub async fn get(&self) -> SharedMockServer {
+        if let Some(server) = self.lock().as_ref() {
+            return server.clone();
+        }
+        // We cannot hold the lock while awaiting - the server is started outside of it.
+        let mut builder = MockServer::builder();
+        builder.skip_test_scope = true;
+        let server: SharedMockServer = builder.start().await.into();
+        self.lock().get_or_insert(server).clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<SharedMockServer>> {
+        // A test panicking while holding the lock cannot leave the slot in a broken state.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for LazyMockServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for LazyMockServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LazyMockServer")
+            .field(&*self.lock())
+            .finish()
+    }
+}
@@ -0,0 +1,108 @@
+use crate::mock_server::{check_verification, ServerHandle};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+type Registry = Rc<RefCell<Vec<ServerHandle>>>;
+
+thread_local! {
+    // The scopes active on the current thread, innermost last.
+    static ACTIVE_SCOPES: RefCell<Vec<Weak<RefCell<Vec<ServerHandle>>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Register a freshly started `MockServer` with the innermost `TestScope` active on the
+/// current thread, if any.
+pub(crate) fn register(server: ServerHandle) {
+    ACTIVE_SCOPES.with(|scopes| {
+        if let Some(registry) = scopes.borrow().iter().rev().find_map(Weak::upgrade) {
+            registry.borrow_mut().push(server);
+        }
+    });
+}
+
+/// A guard tearing down every [`MockServer`] started within its lifetime, on the same thread,
+/// when it goes out of scope.
+///
+/// A [`MockServer`] is verified and shut down when it is dropped. If it never gets dropped
+/// (e.g. it has been leaked into a `static` or kept alive by a forgotten `Arc`) its mocks
+/// keep answering requests and its expectations are never checked: tests sharing the same
+/// process might end up talking to it.  
+/// A `TestScope` closes that gap: when it is dropped, it verifies the expectations of all
+/// the [`MockServer`]s started while it was alive and still running - panicking if they are
+/// not satisfied, like dropping them would - and it shuts them down.  
+/// [`MockServer`]s dropped before the end of the scope are left alone: they have already
+/// been verified and shut down.
+///
+/// Scopes can be nested: a [`MockServer`] belongs to the innermost active scope.
+///
+/// ### Interaction with async runtimes
+///
+/// Scopes are tracked per thread: a [`MockServer`] is registered with a `TestScope` only if
+/// it is started on the thread that created the scope.  
+/// `#[async_std::test]` and `#[tokio::test]` (which uses a single-threaded runtime by
+/// default) run the whole test body on the current thread: create the `TestScope` as the
+/// first statement of the test.  
+/// [`MockServer`]s started on other threads - e.g. in a task spawned on a multi-threaded
+/// runtime, like `#[tokio::test(threaded_scheduler)]` - are not registered.
+///
+/// ### Example:
+/// ```rust
+/// use wiremock::{MockServer, Mock, ResponseTemplate, TestScope};
+/// use wiremock::matchers::method;
+///
+/// #[async_std::main]
+/// async fn main() {
+///     let scope = TestScope::new();
+///
+///     let mock_server = MockServer::start().await;
+///     Mock::given(method("GET"))
+///         .respond_with(ResponseTemplate::new(200))
+///         .mount(&mock_server)
+///         .await;
+///     // Oops, the server is leaked!
+///     std::mem::forget(mock_server);
+///
+///     // ...but the scope shuts it down anyway.
+///     drop(scope);
+/// }
+/// ```
+///
+/// [`MockServer`]: struct.MockServer.html
+#[must_use]
+pub struct TestScope {
+    servers: Registry,
+}
+
+impl TestScope {
+    /// Start tracking the [`MockServer`]s started on the current thread.
+    ///
+    /// [`MockServer`]: struct.MockServer.html
+    pub fn new() -> Self {
+        let servers = Registry::default();
+        ACTIVE_SCOPES.with(|scopes| scopes.borrow_mut().push(Rc::downgrade(&servers)));
+        Self { servers }
+    }
+}
+
+impl Default for TestScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestScope {
+    fn drop(&mut self) {
+        ACTIVE_SCOPES.with(|scopes| {
+            scopes
+                .borrow_mut()
+                .retain(|scope| !scope.ptr_eq(&Rc::downgrade(&self.servers)))
+        });
+        let servers = std::mem::take(&mut *self.servers.borrow_mut());
+        // Shut down every server, even if the verification of one of them fails.
+        let mut verified = Ok(());
+        for server in servers {
+            let outcome = server.shut_down();
+            verified = verified.and(outcome);
+        }
+        check_verification(verified);
+    }
+}
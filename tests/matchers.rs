@@ -0,0 +1,943 @@
+use async_std::net::TcpStream;
+use async_std::prelude::*;
+use http_types::Method;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use wiremock::matchers::{
+    body_is_empty, body_is_present, body_json_unordered_arrays, body_length_eq,
+    body_string_contains, body_string_ends_with, body_string_starts_with, content_length,
+    content_type, has_no_query, header, header_count, if_modified_since, if_none_match,
+    jsonrpc_method, method, method_str, path, path_glob, query_param_count, request_matches, url,
+    IfModifiedSinceMatcher, IfNoneMatchMatcher,
+};
+use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate};
+
+// Sun, 06 Nov 1994 08:49:37 GMT
+fn last_modified() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(784111777)
+}
+
+/// Mount a mock answering `200 OK` to the requests satisfying `matcher`, send the request
+/// built by `request` from the uri of the mock server and return the status code of the response.
+async fn status_for<M, F, R>(matcher: M, request: F) -> u16
+where
+    M: 'static + Match,
+    F: FnOnce(String) -> R,
+    R: Future<Output = Result<surf::Response, surf::Exception>>,
+{
+    let mock_server = MockServer::start().await;
+    Mock::given(matcher)
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    request(mock_server.uri()).await.unwrap().status().as_u16()
+}
+
+#[async_std::test]
+async fn if_modified_since_parses_imf_fixdate() {
+    let get = |uri: String| {
+        surf::get(uri).set_header("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT")
+    };
+    assert_eq!(
+        status_for(if_modified_since(last_modified()), get).await,
+        200
+    );
+    let later = last_modified() + Duration::from_secs(3600);
+    assert_eq!(status_for(if_modified_since(later), get).await, 404);
+    assert_eq!(
+        status_for(IfModifiedSinceMatcher::modified(later), get).await,
+        200
+    );
+}
+
+#[async_std::test]
+async fn if_modified_since_parses_rfc850_dates() {
+    let get = |uri: String| {
+        surf::get(uri).set_header("If-Modified-Since", "Sunday, 06-Nov-94 08:49:37 GMT")
+    };
+    assert_eq!(
+        status_for(if_modified_since(last_modified()), get).await,
+        200
+    );
+    let later = last_modified() + Duration::from_secs(3600);
+    assert_eq!(
+        status_for(IfModifiedSinceMatcher::modified(later), get).await,
+        200
+    );
+}
+
+#[async_std::test]
+async fn if_modified_since_parses_asctime_dates() {
+    let get =
+        |uri: String| surf::get(uri).set_header("If-Modified-Since", "Sun Nov  6 08:49:37 1994");
+    assert_eq!(
+        status_for(if_modified_since(last_modified()), get).await,
+        200
+    );
+    let later = last_modified() + Duration::from_secs(3600);
+    assert_eq!(
+        status_for(IfModifiedSinceMatcher::modified(later), get).await,
+        200
+    );
+}
+
+#[async_std::test]
+async fn if_modified_since_ignores_sub_second_precision() {
+    let get = |uri: String| {
+        surf::get(uri).set_header("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT")
+    };
+    let last_modified = last_modified() + Duration::from_millis(500);
+    assert_eq!(status_for(if_modified_since(last_modified), get).await, 200);
+}
+
+#[async_std::test]
+async fn if_modified_since_does_not_match_invalid_dates() {
+    let get = |uri: String| surf::get(uri).set_header("If-Modified-Since", "yesterday");
+    let modified = IfModifiedSinceMatcher::modified(last_modified());
+    assert_eq!(
+        status_for(if_modified_since(last_modified()), get).await,
+        404
+    );
+    assert_eq!(status_for(modified, get).await, 404);
+}
+
+#[async_std::test]
+async fn content_type_matches_without_parameters() {
+    let post = |content_type: &'static str| {
+        move |uri: String| {
+            surf::post(uri)
+                .body_string("{}".into())
+                .set_header("Content-Type", content_type)
+        }
+    };
+    let status = status_for(content_type("application/json"), post("application/json")).await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn content_type_ignores_parameters_and_case() {
+    let post = |content_type: &'static str| {
+        move |uri: String| {
+            surf::post(uri)
+                .body_string("{}".into())
+                .set_header("Content-Type", content_type)
+        }
+    };
+    let status = status_for(
+        content_type("application/json"),
+        post("Application/JSON; charset=utf-8"),
+    )
+    .await;
+    assert_eq!(status, 200);
+
+    let status = status_for(
+        content_type("text/plain; charset=ascii"),
+        post("text/plain"),
+    )
+    .await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn content_type_does_not_match_a_different_media_type() {
+    let post = |content_type: &'static str| {
+        move |uri: String| {
+            surf::post(uri)
+                .body_string("{}".into())
+                .set_header("Content-Type", content_type)
+        }
+    };
+    let status = status_for(content_type("application/json"), post("application/jsonp")).await;
+    assert_eq!(status, 404);
+}
+
+#[async_std::test]
+async fn request_matches_can_combine_multiple_fields() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let matcher = request_matches(|request: &Request| {
+        request.method == Some(Method::Post) && request.url.path() == "/x" && request.body.len() > 5
+    });
+    Mock::given(matcher)
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let long_body = surf::post(mock_server.uri_for("x"))
+        .body_string("long enough".into())
+        .await
+        .unwrap()
+        .status();
+    let short_body = surf::post(mock_server.uri_for("x"))
+        .body_string("tiny".into())
+        .await
+        .unwrap()
+        .status();
+    let wrong_path = surf::post(mock_server.uri_for("y"))
+        .body_string("long enough".into())
+        .await
+        .unwrap()
+        .status();
+
+    // Assert
+    assert_eq!(long_body.as_u16(), 200);
+    assert_eq!(short_body.as_u16(), 404);
+    assert_eq!(wrong_path.as_u16(), 404);
+}
+
+#[async_std::test]
+async fn query_param_count_matches_the_exact_number_of_parameters() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(status_for(query_param_count(0), get("")).await, 200);
+    assert_eq!(status_for(query_param_count(2), get("?a=1&b=2")).await, 200);
+    assert_eq!(status_for(query_param_count(1), get("?a=1&b=2")).await, 404);
+    assert_eq!(status_for(query_param_count(3), get("?a=1&b=2")).await, 404);
+}
+
+#[async_std::test]
+async fn query_param_count_counts_every_occurrence_of_repeated_keys() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(
+        status_for(query_param_count(3), get("?a=1&a=2&b=3")).await,
+        200
+    );
+    assert_eq!(
+        status_for(query_param_count(2), get("?a=1&a=2&b=3")).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn has_no_query_treats_an_empty_query_as_no_query() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(path("/x"))
+        .and(has_no_query())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut statuses = vec![];
+    for target in &["/x", "/x?", "/x?a=1"] {
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+        statuses.push(raw_status(&mock_server, request.as_bytes()).await);
+    }
+
+    // Assert
+    assert_eq!(statuses, vec![200, 200, 404]);
+}
+
+#[async_std::test]
+async fn path_glob_single_star_matches_exactly_one_segment() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(
+        status_for(path_glob("/users/*/posts"), get("/users/42/posts")).await,
+        200
+    );
+    assert_eq!(
+        status_for(path_glob("users/*/posts"), get("/users/42/posts?page=2")).await,
+        200
+    );
+    assert_eq!(
+        status_for(path_glob("/users/*/posts"), get("/users/posts")).await,
+        404
+    );
+    assert_eq!(
+        status_for(path_glob("/users/*/posts"), get("/users//posts")).await,
+        404
+    );
+    assert_eq!(
+        status_for(path_glob("/users/*/posts"), get("/users/42/x/posts")).await,
+        404
+    );
+    assert_eq!(
+        status_for(path_glob("/users/*"), get("/users/42/posts")).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn path_glob_double_star_matches_across_segments() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(
+        status_for(path_glob("/static/**"), get("/static/css/main.css")).await,
+        200
+    );
+    assert_eq!(
+        status_for(path_glob("/static/**"), get("/static")).await,
+        200
+    );
+    assert_eq!(
+        status_for(path_glob("/**/posts"), get("/users/42/posts")).await,
+        200
+    );
+    assert_eq!(status_for(path_glob("/a/**/z"), get("/a/z")).await, 200);
+    assert_eq!(status_for(path_glob("/a/**/z"), get("/a/b/c/z")).await, 200);
+    assert_eq!(status_for(path_glob("/a/**/z"), get("/a/b/c/y")).await, 404);
+    assert_eq!(
+        status_for(path_glob("/static/**"), get("/assets/main.css")).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn body_json_unordered_arrays_ignores_array_order_at_every_level() {
+    use serde_json::json;
+
+    let post =
+        |body: serde_json::Value| move |uri: String| surf::post(uri).body_json(&body).unwrap();
+    let expected = json!({"ids": [1, 2, 3], "nested": [{"tags": ["x", "y"]}, {"tags": []}]});
+    let shuffled = json!({"nested": [{"tags": []}, {"tags": ["y", "x"]}], "ids": [3, 1, 2]});
+    assert_eq!(
+        status_for(body_json_unordered_arrays(expected), post(shuffled)).await,
+        200
+    );
+}
+
+#[async_std::test]
+async fn body_json_unordered_arrays_compares_arrays_as_multisets() {
+    use serde_json::json;
+
+    let post =
+        |body: serde_json::Value| move |uri: String| surf::post(uri).body_json(&body).unwrap();
+    assert_eq!(
+        status_for(
+            body_json_unordered_arrays(json!([1, 1, 2])),
+            post(json!([2, 1, 1]))
+        )
+        .await,
+        200
+    );
+    assert_eq!(
+        status_for(
+            body_json_unordered_arrays(json!([1, 1, 2])),
+            post(json!([1, 2, 2]))
+        )
+        .await,
+        404
+    );
+    assert_eq!(
+        status_for(
+            body_json_unordered_arrays(json!([1, 2])),
+            post(json!([1, 2, 3]))
+        )
+        .await,
+        404
+    );
+    assert_eq!(
+        status_for(
+            body_json_unordered_arrays(json!({"a": [1]})),
+            post(json!({"a": [1], "b": 2}))
+        )
+        .await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn url_ignores_the_order_of_query_parameters() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(
+        status_for(url("/search?a=1&b=2"), get("/search?b=2&a=1")).await,
+        200
+    );
+    assert_eq!(
+        status_for(url("search?a=1&a=2"), get("/search?a=2&a=1")).await,
+        200
+    );
+    assert_eq!(
+        status_for(url("/search?q=a+b"), get("/search?q=a%20b")).await,
+        200
+    );
+    assert_eq!(status_for(url("/search#top"), get("/search")).await, 200);
+}
+
+#[async_std::test]
+async fn url_requires_exactly_the_expected_path_and_query() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(
+        status_for(url("/search?a=1"), get("/search?a=1&b=2")).await,
+        404
+    );
+    assert_eq!(
+        status_for(url("/search?a=1&a=1"), get("/search?a=1")).await,
+        404
+    );
+    assert_eq!(
+        status_for(url("/search?a=1"), get("/search?a=2")).await,
+        404
+    );
+    assert_eq!(status_for(url("/search"), get("/search?a=1")).await, 404);
+    assert_eq!(status_for(url("/search?a=1"), get("/other?a=1")).await, 404);
+}
+
+#[async_std::test]
+async fn url_decodes_the_path() {
+    let get = |path_and_query: &'static str| move |uri: String| surf::get(uri + path_and_query);
+    assert_eq!(status_for(url("/caf%C3%A9"), get("/caf%c3%a9")).await, 200);
+    assert_eq!(status_for(url("/a%2Fb"), get("/a%2fb")).await, 200);
+}
+
+/// Write `request` on a raw TCP connection to `mock_server` and return the status code
+/// of the response.
+async fn raw_status(mock_server: &MockServer, request: &[u8]) -> u16 {
+    let mut stream = TcpStream::connect(mock_server.address()).await.unwrap();
+    stream.write_all(request).await.unwrap();
+    let mut buffer = [0u8; 1024];
+    let bytes_read = stream.read(&mut buffer).await.unwrap();
+    let status_line = String::from_utf8_lossy(&buffer[..bytes_read]).to_string();
+    status_line.split(' ').nth(1).unwrap().parse().unwrap()
+}
+
+#[async_std::test]
+async fn method_accepts_both_the_enum_and_strings() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method(Method::Put))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method(String::from("PROPFIND")))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let put = surf::put(mock_server.uri()).await.unwrap().status();
+    let delete = surf::delete(mock_server.uri()).await.unwrap().status();
+    let propfind = raw_status(
+        &mock_server,
+        b"PROPFIND / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+    )
+    .await;
+    let get = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_eq!(put.as_u16(), 201);
+    assert_eq!(delete.as_u16(), 204);
+    assert_eq!(propfind, 200);
+    assert_eq!(get.as_u16(), 404);
+}
+
+#[async_std::test]
+async fn method_enum_and_its_spelling_match_the_same_requests() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method(Method::Patch))
+        .and(method("PATCH"))
+        .and(method_str("PATCH"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let patch = surf::patch(mock_server.uri()).await.unwrap().status();
+    let post = surf::post(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_eq!(patch.as_u16(), 200);
+    assert_eq!(post.as_u16(), 404);
+    assert_eq!(method(Method::Patch).name(), method_str("PATCH").name());
+}
+
+#[test]
+#[should_panic(expected = "methods are case-sensitive, use `Method::Get`")]
+fn method_rejects_standard_methods_with_the_wrong_case() {
+    method("get");
+}
+
+#[async_std::test]
+async fn method_str_matches_webdav_methods() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method_str("PROPFIND"))
+        .and(path("/files"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = raw_status(
+        &mock_server,
+        b"PROPFIND /files HTTP/1.1\r\nHost: localhost\r\nDepth: 1\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn extension_methods_are_not_parsed_into_a_standard_method() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method(Method::Connect))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(request_matches(|request: &Request| {
+        request.method.is_none() && request.raw_method == "PROPFIND"
+    }))
+    .respond_with(ResponseTemplate::new(202))
+    .mount(&mock_server)
+    .await;
+
+    // Act
+    let status = raw_status(
+        &mock_server,
+        b"PROPFIND /files HTTP/1.1\r\nHost: localhost\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, 202);
+}
+
+#[async_std::test]
+async fn absolute_form_targets_are_preserved() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(path("/resource"))
+        .and(request_matches(|request: &Request| {
+            request.raw_target == "http://example.com/resource?a=b"
+                && request.url.host_str() == Some("example.com")
+        }))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = raw_status(
+        &mock_server,
+        b"GET http://example.com/resource?a=b HTTP/1.1\r\nHost: example.com\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn header_count_counts_every_occurrence_of_a_header() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(header_count("x-forwarded-for", 2))
+        .and(header("x-forwarded-for", "10.0.0.2"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let duplicated = raw_status(
+        &mock_server,
+        b"GET / HTTP/1.1\r\nHost: localhost\r\n\
+          X-Forwarded-For: 10.0.0.1\r\nX-Forwarded-For: 10.0.0.2\r\n\r\n",
+    )
+    .await;
+    let single = raw_status(
+        &mock_server,
+        b"GET / HTTP/1.1\r\nHost: localhost\r\nX-Forwarded-For: 10.0.0.2\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(duplicated, 200);
+    assert_eq!(single, 404);
+}
+
+#[async_std::test]
+async fn header_count_of_zero_matches_missing_headers() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(header_count("content-length", 0))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = raw_status(&mock_server, b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+
+    // Assert
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn content_length_and_body_length_can_disagree() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(content_length(10))
+        .and(body_length_eq(5))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act - `Transfer-Encoding` wins over `Content-Length` when reading the body
+    let status = raw_status(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\n\
+          Content-Length: 10\r\nTransfer-Encoding: chunked\r\n\r\n\
+          5\r\nhello\r\n0\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn chunked_bodies_are_read_whatever_the_case_and_the_previous_codings() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(body_length_eq(5))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = raw_status(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: gzip, Chunked\r\n\r\n\
+          5\r\nhello\r\n0\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn content_length_does_not_match_missing_headers() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(content_length(5))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = raw_status(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n\
+          5\r\nhello\r\n0\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, 404);
+}
+
+#[async_std::test]
+async fn body_is_empty_matches_missing_and_zero_length_bodies() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(body_is_empty())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let missing = raw_status(&mock_server, b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+    let zero_length = raw_status(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(missing, 200);
+    assert_eq!(zero_length, 200);
+    assert_eq!(status_for(body_is_empty(), post(b"hello")).await, 404);
+}
+
+#[async_std::test]
+async fn body_is_present_requires_at_least_one_byte() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    assert_eq!(status_for(body_is_present(), post(b"hello")).await, 200);
+    assert_eq!(status_for(body_is_present(), post(b"")).await, 404);
+}
+
+#[async_std::test]
+async fn body_string_contains_matches_a_substring() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    assert_eq!(
+        status_for(body_string_contains("lo wo"), post(b"hello world")).await,
+        200
+    );
+    assert_eq!(
+        status_for(body_string_contains("bye"), post(b"hello world")).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn body_string_starts_with_matches_a_prefix() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    assert_eq!(
+        status_for(body_string_starts_with("hello"), post(b"hello world")).await,
+        200
+    );
+    assert_eq!(
+        status_for(body_string_starts_with("world"), post(b"hello world")).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn body_string_ends_with_matches_a_suffix() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    assert_eq!(
+        status_for(body_string_ends_with("world"), post(b"hello world")).await,
+        200
+    );
+    assert_eq!(
+        status_for(body_string_ends_with("hello"), post(b"hello world")).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn body_string_matchers_do_not_match_non_utf8_bodies() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    let body = b"hello \xFF world";
+    assert_eq!(
+        status_for(body_string_contains("hello"), post(body)).await,
+        404
+    );
+    assert_eq!(
+        status_for(body_string_starts_with("hello"), post(body)).await,
+        404
+    );
+    assert_eq!(
+        status_for(body_string_ends_with("world"), post(body)).await,
+        404
+    );
+}
+
+#[cfg(feature = "gzip")]
+mod gzip {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use serde_json::json;
+    use std::io::Write;
+    use wiremock::matchers::body_json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn status_for_gzipped_json(mock_server: MockServer) -> u16 {
+        let body = json!({"hello": "world"});
+        Mock::given(body_json(&body))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        surf::post(mock_server.uri())
+            .set_header("Content-Encoding", "gzip")
+            .body_bytes(gzip(body.to_string().as_bytes()))
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[async_std::test]
+    async fn body_json_matches_a_gzipped_body_if_decompression_is_enabled() {
+        let mock_server = MockServer::builder()
+            .decompress_request_bodies()
+            .start()
+            .await;
+        assert_eq!(status_for_gzipped_json(mock_server).await, 200);
+    }
+
+    #[async_std::test]
+    async fn gzipped_bodies_are_left_untouched_by_default() {
+        let mock_server = MockServer::start().await;
+        assert_eq!(status_for_gzipped_json(mock_server).await, 404);
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+mod jsonschema {
+    use serde_json::json;
+    use wiremock::matchers::body_json_matches_schema;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn statuses_for(bodies: &[&[u8]]) -> Vec<u16> {
+        let mock_server = MockServer::start().await;
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer", "minimum": 0 }
+            },
+            "required": ["name"]
+        });
+        Mock::given(body_json_matches_schema(schema))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let mut statuses = vec![];
+        for body in bodies {
+            let response = surf::post(mock_server.uri())
+                .body_bytes(body)
+                .await
+                .unwrap();
+            statuses.push(response.status().as_u16());
+        }
+        statuses
+    }
+
+    #[async_std::test]
+    async fn bodies_validating_against_the_schema_match() {
+        let bodies: &[&[u8]] = &[
+            br#"{"name": "wiremock"}"#,
+            br#"{"name": "wiremock", "age": 3}"#,
+        ];
+        assert_eq!(statuses_for(bodies).await, vec![200, 200]);
+    }
+
+    #[async_std::test]
+    async fn bodies_violating_the_schema_do_not_match() {
+        let bodies: &[&[u8]] = &[
+            br#"{"age": 3}"#,
+            br#"{"name": "wiremock", "age": -1}"#,
+            b"not json",
+        ];
+        assert_eq!(statuses_for(bodies).await, vec![404, 404, 404]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to compile the JSON Schema")]
+    fn invalid_schemas_panic_at_construction() {
+        body_json_matches_schema(json!({ "type": "not-a-type" }));
+    }
+}
+
+#[async_std::test]
+async fn if_none_match_compares_weak_entity_tags() {
+    let get =
+        |etags: &'static str| move |uri: String| surf::get(uri).set_header("If-None-Match", etags);
+    let weak = r#"W/"v1""#;
+    assert_eq!(status_for(if_none_match(weak), get(weak)).await, 200);
+    assert_eq!(
+        status_for(IfNoneMatchMatcher::strong(weak), get(weak)).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn if_none_match_compares_strong_entity_tags() {
+    let get =
+        |etags: &'static str| move |uri: String| surf::get(uri).set_header("If-None-Match", etags);
+    let strong = r#""v1""#;
+    assert_eq!(status_for(if_none_match(strong), get(strong)).await, 200);
+    assert_eq!(
+        status_for(IfNoneMatchMatcher::strong(strong), get(strong)).await,
+        200
+    );
+    assert_eq!(
+        status_for(IfNoneMatchMatcher::strong(strong), get(r#""v2""#)).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn if_none_match_compares_mixed_entity_tags() {
+    let get =
+        |etags: &'static str| move |uri: String| surf::get(uri).set_header("If-None-Match", etags);
+    assert_eq!(
+        status_for(if_none_match(r#""v1""#), get(r#"W/"v1""#)).await,
+        200
+    );
+    assert_eq!(
+        status_for(if_none_match(r#"W/"v1""#), get(r#""v1""#)).await,
+        200
+    );
+    assert_eq!(
+        status_for(IfNoneMatchMatcher::strong(r#""v1""#), get(r#"W/"v1""#)).await,
+        404
+    );
+    assert_eq!(
+        status_for(IfNoneMatchMatcher::strong(r#"W/"v1""#), get(r#""v1""#)).await,
+        404
+    );
+}
+
+#[async_std::test]
+async fn if_none_match_handles_lists_wildcards_and_missing_headers() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(if_none_match(r#""a,b""#))
+        .respond_with(ResponseTemplate::new(304))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut statuses = vec![];
+    for header in &[r#""x", W/"a,b""#, r#""a", "b""#, "*", "garbage"] {
+        let response = surf::get(mock_server.uri())
+            .set_header("If-None-Match", *header)
+            .await
+            .unwrap();
+        statuses.push(response.status().as_u16());
+    }
+    let missing = surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert
+    assert_eq!(statuses, vec![304, 404, 304, 404]);
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[test]
+#[should_panic(expected = "v1 is not a valid entity tag.")]
+fn if_none_match_rejects_invalid_entity_tags() {
+    if_none_match("v1");
+}
+
+#[async_std::test]
+async fn jsonrpc_method_matches_the_method_of_single_requests() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    let call = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let notification = br#"{"jsonrpc": "2.0", "method": "subtract"}"#;
+    assert_eq!(
+        status_for(jsonrpc_method("subtract"), post(call)).await,
+        200
+    );
+    assert_eq!(
+        status_for(jsonrpc_method("subtract"), post(notification)).await,
+        200
+    );
+    assert_eq!(status_for(jsonrpc_method("add"), post(call)).await, 404);
+}
+
+#[async_std::test]
+async fn jsonrpc_method_rejects_non_conformant_requests_and_batches() {
+    let post = |body: &'static [u8]| move |uri: String| surf::post(uri).body_bytes(body);
+    let legacy = br#"{"method": "subtract", "params": [42, 23], "id": 1}"#;
+    let batch = br#"[{"jsonrpc": "2.0", "method": "subtract", "id": 1}]"#;
+    assert_eq!(
+        status_for(jsonrpc_method("subtract"), post(legacy)).await,
+        404
+    );
+    assert_eq!(
+        status_for(jsonrpc_method("subtract"), post(batch)).await,
+        404
+    );
+    assert_eq!(
+        status_for(jsonrpc_method("subtract"), post(b"subtract")).await,
+        404
+    );
+}
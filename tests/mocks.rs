@@ -11,6 +11,15 @@ async fn new_starts_the_server() {
     assert!(TcpStream::connect(&mock_server.address()).is_ok())
 }
 
+#[async_std::test]
+async fn try_start_starts_the_server() {
+    // Act
+    let mock_server = MockServer::try_start().await.unwrap();
+
+    // Assert
+    assert!(TcpStream::connect(mock_server.address()).is_ok())
+}
+
 #[async_std::test]
 async fn returns_404_if_nothing_matches() {
     // Arrange - no mocks mounted
@@ -110,3 +119,1429 @@ async fn two_route_mocks() {
     assert_eq!(first_response.body_string().await.unwrap(), "aaa");
     assert_eq!(second_response.body_string().await.unwrap(), "bbb");
 }
+
+#[async_std::test]
+async fn url_and_uri_point_to_the_same_server() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act
+    let url = mock_server.url();
+
+    // Assert
+    assert_eq!(url.as_str().trim_end_matches('/'), mock_server.uri());
+    assert_eq!(url.port(), Some(mock_server.address().port()));
+}
+
+#[async_std::test]
+async fn uri_for_joins_paths_with_a_single_slash() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    // Assert
+    assert_eq!(mock_server.uri_for("hello"), format!("{}/hello", base));
+    assert_eq!(mock_server.uri_for("/hello"), format!("{}/hello", base));
+    assert_eq!(mock_server.uri_for("//hello"), format!("{}/hello", base));
+    assert_eq!(mock_server.uri_for(""), format!("{}/", base));
+    assert_eq!(
+        mock_server.uri_for("/hello/world?a=1&b=2"),
+        format!("{}/hello/world?a=1&b=2", base)
+    );
+}
+
+#[async_std::test]
+async fn uri_for_can_be_used_to_reach_a_mock() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(PathExactMatcher::new("hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let with_slash = surf::get(mock_server.uri_for("/hello?a=1"))
+        .await
+        .unwrap()
+        .status();
+    let without_slash = surf::get(mock_server.uri_for("hello"))
+        .await
+        .unwrap()
+        .status();
+
+    // Assert
+    assert_eq!(with_slash.as_u16(), 200);
+    assert_eq!(without_slash.as_u16(), 200);
+}
+
+#[async_std::test]
+async fn total_and_unmatched_requests_are_zero_without_calls() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Assert
+    assert_eq!(mock_server.total_requests().await, 0);
+    assert_eq!(mock_server.unmatched_requests().await, 0);
+}
+
+#[async_std::test]
+async fn total_and_unmatched_requests_count_all_calls() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(PathExactMatcher::new("hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    surf::get(mock_server.uri_for("hello")).await.unwrap();
+    surf::get(mock_server.uri_for("hello")).await.unwrap();
+    surf::get(mock_server.uri_for("missing")).await.unwrap();
+
+    // Assert
+    assert_eq!(mock_server.total_requests().await, 3);
+    assert_eq!(mock_server.unmatched_requests().await, 1);
+
+    // Resetting the server sets the counts back to zero
+    mock_server.reset().await;
+    assert_eq!(mock_server.total_requests().await, 0);
+    assert_eq!(mock_server.unmatched_requests().await, 0);
+}
+
+#[async_std::test]
+async fn accept_concurrency_limits_the_number_of_connections_served_in_parallel() {
+    use async_std::future::timeout;
+    use async_std::prelude::*;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::builder()
+        .accept_concurrency(1)
+        .listen_backlog(16)
+        .start()
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    let request = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let mut buffer = [0u8; 1024];
+
+    // Act - the first connection is kept alive after getting its response
+    let mut first = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+    first.write_all(request).await.unwrap();
+    let bytes_read = first.read(&mut buffer).await.unwrap();
+    assert!(buffer[..bytes_read].starts_with(b"HTTP/1.1 200 OK"));
+
+    let mut second = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+    second.write_all(request).await.unwrap();
+
+    // Assert - the second connection is not served while the first one is open...
+    let outcome = timeout(Duration::from_millis(300), second.read(&mut buffer)).await;
+    assert!(outcome.is_err());
+
+    // ...but it is as soon as the first one is closed.
+    drop(first);
+    let bytes_read = timeout(Duration::from_secs(5), second.read(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(buffer[..bytes_read].starts_with(b"HTTP/1.1 200 OK"));
+}
+
+#[async_std::test]
+async fn scoped_mocks_only_record_the_requests_they_matched() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let get_guard = mock_server
+        .register_scoped(Mock::given(method("GET")).respond_with(ResponseTemplate::new(200)))
+        .await;
+    let post_guard = mock_server
+        .register_scoped(Mock::given(method("POST")).respond_with(ResponseTemplate::new(201)))
+        .await;
+
+    // Act
+    surf::get(mock_server.uri()).await.unwrap();
+    surf::post(mock_server.uri())
+        .body_string("first".into())
+        .await
+        .unwrap();
+    surf::post(mock_server.uri())
+        .body_string("second".into())
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(get_guard.received_requests().await.len(), 1);
+    let bodies: Vec<Vec<u8>> = post_guard
+        .received_requests()
+        .await
+        .into_iter()
+        .map(|r| r.body)
+        .collect();
+    assert_eq!(bodies, vec![b"first".to_vec(), b"second".to_vec()]);
+}
+
+#[async_std::test]
+async fn scoped_mocks_are_removed_when_their_guard_is_dropped() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let guard = mock_server
+        .register_scoped(Mock::given(method("GET")).respond_with(ResponseTemplate::new(200)))
+        .await;
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+    assert_eq!(status.as_u16(), 200);
+
+    // Act
+    drop(guard);
+
+    // Assert
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+    assert_eq!(status.as_u16(), 404);
+}
+
+#[async_std::test]
+#[should_panic(expected = "scoped mock expectations have not been satisfied")]
+async fn dropping_a_guard_panics_if_the_expectation_is_not_satisfied() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let mock = Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1);
+
+    // Act - we never call the mock
+    let _guard = mock_server.register_scoped(mock).await;
+}
+
+#[async_std::test]
+async fn only_on_call_matches_a_single_attempt() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .only_on_call(3)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut statuses = vec![];
+    for _ in 0..5 {
+        let status = surf::get(mock_server.uri()).await.unwrap().status();
+        statuses.push(status.as_u16());
+    }
+
+    // Assert
+    assert_eq!(statuses, vec![404, 404, 500, 404, 404]);
+}
+
+#[async_std::test]
+async fn only_on_call_does_not_count_requests_failing_the_matchers() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(201))
+        .only_on_call(2)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let first = surf::post(mock_server.uri()).await.unwrap().status();
+    let unrelated = surf::get(mock_server.uri()).await.unwrap().status();
+    let second = surf::post(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_eq!(first.as_u16(), 404);
+    assert_eq!(unrelated.as_u16(), 404);
+    assert_eq!(second.as_u16(), 201);
+}
+
+#[async_std::test]
+#[should_panic(expected = "the mock server died")]
+async fn is_running_detects_the_death_of_a_background_task() {
+    use std::time::Duration;
+    use wiremock::Request;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    assert!(mock_server.is_running());
+    // Panicking in a matcher takes down the task matching requests against mocks.
+    Mock::given(|_: &Request| -> bool { panic!("Simulated task death") })
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_ne!(status.as_u16(), 200);
+    for _ in 0..40 {
+        if !mock_server.is_running() {
+            break;
+        }
+        async_std::task::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(!mock_server.is_running());
+}
+
+#[async_std::test]
+#[should_panic(expected = "the mock server died")]
+async fn expectations_fail_if_a_matcher_panicked() {
+    use wiremock::Request;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(|_: &Request| -> bool { panic!("Simulated matcher failure") })
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Act - the mock state dies with the task matching requests
+    surf::get(mock_server.uri()).await.unwrap();
+    while mock_server.is_running() {
+        async_std::task::sleep(std::time::Duration::from_millis(50)).await;
+    }
+}
+
+#[async_std::test]
+async fn reset_counters_keeps_mocks_but_forgets_about_past_requests() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let guard = mock_server
+        .register_scoped(
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1),
+        )
+        .await;
+    surf::get(mock_server.uri()).await.unwrap();
+    surf::post(mock_server.uri()).await.unwrap();
+
+    // Act
+    mock_server.reset_counters().await;
+
+    // Assert
+    assert_eq!(mock_server.total_requests().await, 0);
+    assert_eq!(mock_server.unmatched_requests().await, 0);
+    assert!(guard.received_requests().await.is_empty());
+    // The mock is still there and its expectation is checked from scratch.
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+    assert_eq!(status.as_u16(), 200);
+    assert_eq!(guard.received_requests().await.len(), 1);
+}
+
+#[async_std::test]
+async fn a_100_continue_is_sent_to_clients_expecting_it() {
+    use async_std::future::timeout;
+    use async_std::prelude::*;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(wiremock::matchers::body_string("hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    let mut stream = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+    let mut buffer = [0u8; 1024];
+
+    // Act - the client does not send the body until it gets the go-ahead
+    stream
+        .write_all(
+            b"POST / HTTP/1.1\r\nHost: localhost\r\n\
+              Content-Length: 5\r\nExpect: 100-continue\r\n\r\n",
+        )
+        .await
+        .unwrap();
+    let bytes_read = timeout(Duration::from_secs(5), stream.read(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buffer[..bytes_read], b"HTTP/1.1 100 Continue\r\n\r\n");
+    stream.write_all(b"hello").await.unwrap();
+
+    // Assert
+    let bytes_read = timeout(Duration::from_secs(5), stream.read(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(buffer[..bytes_read].starts_with(b"HTTP/1.1 200 OK"));
+}
+
+#[async_std::test]
+async fn capture_body_into_appends_each_matched_body_exactly_once() {
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::body_string_contains;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let sink = Arc::new(Mutex::new(Vec::new()));
+    Mock::given(method("POST"))
+        .and(body_string_contains("part"))
+        .respond_with(ResponseTemplate::new(200))
+        .capture_body_into(sink.clone())
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    for body in &["part one,", "unrelated", "part two"] {
+        surf::post(mock_server.uri())
+            .body_string(body.to_string())
+            .await
+            .unwrap();
+    }
+
+    // Assert
+    assert_eq!(*sink.lock().unwrap(), b"part one,part two".to_vec());
+}
+
+#[async_std::test]
+async fn on_match_is_invoked_for_each_matched_request() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use wiremock::matchers::body_string_contains;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let n_calls = Arc::new(AtomicUsize::new(0));
+    let counter = n_calls.clone();
+    Mock::given(method("POST"))
+        .and(body_string_contains("part"))
+        .respond_with(ResponseTemplate::new(200))
+        .on_match(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    for body in &["part one,", "unrelated", "part two"] {
+        surf::post(mock_server.uri())
+            .body_string(body.to_string())
+            .await
+            .unwrap();
+    }
+
+    // Assert
+    assert_eq!(n_calls.load(Ordering::SeqCst), 2);
+}
+
+#[async_std::test]
+async fn on_match_runs_after_the_body_has_been_captured() {
+    use std::sync::{Arc, Mutex};
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let sink = Arc::new(Mutex::new(Vec::new()));
+    let seen_by_callback = Arc::new(Mutex::new(Vec::new()));
+    let (callback_sink, callback_seen) = (sink.clone(), seen_by_callback.clone());
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .capture_body_into(sink.clone())
+        .on_match(move |_| {
+            let captured = callback_sink.lock().unwrap().clone();
+            *callback_seen.lock().unwrap() = captured;
+        })
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    surf::post(mock_server.uri())
+        .body_string("hello".into())
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(*seen_by_callback.lock().unwrap(), b"hello".to_vec());
+}
+
+#[async_std::test]
+async fn idle_connections_are_closed_after_the_idle_timeout() {
+    use async_std::future::timeout;
+    use async_std::prelude::*;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::builder()
+        .idle_timeout(Duration::from_millis(200))
+        .start()
+        .await;
+    let mut stream = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+
+    // Act - we never send a single byte
+    let mut buffer = [0u8; 16];
+    let bytes_read = timeout(Duration::from_secs(5), stream.read(&mut buffer)).await;
+
+    // Assert - the server hung up on us (EOF), without a response
+    assert_eq!(bytes_read.unwrap().unwrap(), 0);
+}
+
+#[async_std::test]
+async fn drain_received_requests_takes_and_clears_the_log() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    surf::get(mock_server.uri_for("/matched")).await.unwrap();
+    surf::post(mock_server.uri_for("/unmatched")).await.unwrap();
+
+    // Act
+    let drained = mock_server.drain_received_requests().await;
+    surf::get(mock_server.uri_for("/after")).await.unwrap();
+
+    // Assert
+    let paths: Vec<_> = drained.iter().map(|r| r.url.path().to_owned()).collect();
+    assert_eq!(paths, vec!["/matched", "/unmatched"]);
+    let remaining = mock_server.received_requests().await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].url.path(), "/after");
+}
+
+#[async_std::test]
+async fn recorded_requests_are_timestamped_on_receipt() {
+    use std::time::{Duration, Instant};
+    use wiremock::Request;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let start = Instant::now();
+
+    // Act
+    surf::get(mock_server.uri()).await.unwrap();
+    async_std::task::sleep(Duration::from_millis(200)).await;
+    surf::get(mock_server.uri()).await.unwrap();
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.received_at >= start));
+    let intervals = Request::inter_arrival_times(&requests);
+    assert_eq!(intervals.len(), 2);
+    assert!(intervals[0] >= Duration::from_millis(200));
+    assert!(intervals[1] < Duration::from_millis(200));
+    assert!(Request::inter_arrival_times(&requests[..1]).is_empty());
+}
+
+#[async_std::test]
+async fn drain_received_requests_is_empty_if_recording_is_disabled() {
+    // Arrange
+    let mock_server = MockServer::builder()
+        .disable_request_recording()
+        .start()
+        .await;
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Act
+    let drained = mock_server.drain_received_requests().await;
+
+    // Assert
+    assert!(drained.is_empty());
+    assert!(mock_server.received_requests().await.is_none());
+}
+
+#[async_std::test]
+async fn scoped_mocks_do_not_record_requests_if_recording_is_disabled() {
+    // Arrange
+    let mock_server = MockServer::builder()
+        .disable_request_recording()
+        .start()
+        .await;
+    let guard = mock_server
+        .register_scoped(
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1),
+        )
+        .await;
+
+    // Act
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_eq!(status.as_u16(), 200);
+    assert!(guard.received_requests().await.is_empty());
+}
+
+#[async_std::test]
+async fn test_scopes_shut_down_leaked_servers() {
+    use wiremock::TestScope;
+
+    // Arrange
+    let scope = TestScope::new();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    let uri = mock_server.uri();
+    std::mem::forget(mock_server);
+    assert_eq!(surf::get(&uri).await.unwrap().status().as_u16(), 200);
+
+    // Act
+    drop(scope);
+
+    // Assert - the mock is gone for good
+    let outcome = surf::get(&uri).await;
+    assert!(outcome.map_or(true, |r| r.status().as_u16() != 200));
+}
+
+#[async_std::test]
+#[should_panic(expected = "Verification failed")]
+async fn test_scopes_verify_the_expectations_of_leaked_servers() {
+    use wiremock::TestScope;
+
+    // Arrange
+    let _scope = TestScope::new();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Act - the server is leaked, `_scope` is dropped at the end of the test
+    std::mem::forget(mock_server);
+}
+
+#[async_std::test]
+async fn test_scopes_leave_dropped_servers_alone() {
+    use wiremock::TestScope;
+
+    // Arrange
+    let scope = TestScope::new();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Act - verified when dropped, not again when the scope ends
+    drop(mock_server);
+    drop(scope);
+}
+
+#[async_std::test]
+async fn mounted_mocks_describes_every_mounted_mock() {
+    use wiremock::matchers::{header, path};
+    use wiremock::{Match, Request};
+
+    struct Custom;
+
+    impl Match for Custom {
+        fn matches(&self, _request: &Request) -> bool {
+            true
+        }
+
+        fn name(&self) -> String {
+            "always".into()
+        }
+    }
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .named("Hello")
+        .expect(2..=3)
+        .mount(&mock_server)
+        .await;
+    Mock::given(Custom)
+        .and(header("x-test", "1"))
+        .and(|_: &Request| true)
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    surf::get(mock_server.uri_for("/hello")).await.unwrap();
+
+    // Act
+    let mocks = mock_server.mounted_mocks().await;
+
+    // Assert
+    assert_eq!(mocks.len(), 2);
+    assert_eq!(mocks[0].name.as_deref(), Some("Hello"));
+    assert_eq!(mocks[0].matchers[0], "MethodExactMatcher(\"GET\")");
+    assert!(mocks[0].matchers[1].starts_with("PathExactMatcher"));
+    assert_eq!(mocks[0].expectation, "2..=3");
+    assert_eq!(mocks[0].n_matched_requests, 1);
+    assert!(!mocks[0].expectation_satisfied);
+
+    assert_eq!(mocks[1].name, None);
+    assert_eq!(mocks[1].matchers.len(), 3);
+    assert_eq!(mocks[1].matchers[0], "always");
+    assert!(mocks[1].matchers[1].starts_with("HeaderExactMatcher"));
+    assert!(mocks[1].matchers[2].contains("closure"));
+    assert_eq!(mocks[1].expectation, "..");
+    assert_eq!(mocks[1].n_matched_requests, 0);
+
+    mock_server.reset().await;
+}
+
+#[async_std::test]
+async fn mocks_only_match_within_their_active_window() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let now = std::time::Instant::now();
+    let window = std::time::Duration::from_millis(300);
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .active_between(now, now + window)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let during = surf::get(mock_server.uri()).await.unwrap().status();
+    async_std::task::sleep(window).await;
+    let after = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_eq!(during.as_u16(), 503);
+    assert_eq!(after.as_u16(), 200);
+}
+
+#[async_std::test]
+async fn mocks_do_not_match_before_their_active_window() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let start = std::time::Instant::now() + std::time::Duration::from_secs(3600);
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .active_between(start, start + std::time::Duration::from_secs(1))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert_eq!(status.as_u16(), 404);
+}
+
+async fn raw_response(mock_server: &MockServer, request: &[u8]) -> String {
+    use async_std::prelude::*;
+
+    let mut stream = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+    stream.write_all(request).await.unwrap();
+    // The server closes the connection after a `400`: we can read up to EOF.
+    let mut response = Vec::new();
+    async_std::future::timeout(
+        std::time::Duration::from_secs(5),
+        stream.read_to_end(&mut response),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    String::from_utf8(response).unwrap()
+}
+
+#[async_std::test]
+async fn garbage_requests_get_a_400_and_the_server_stays_alive() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act - no blank line: the server must not wait for the end of the head
+    let response = raw_response(&mock_server, b"\x00\x01 this is not HTTP\r\n").await;
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert_eq!(status.as_u16(), 404);
+}
+
+#[async_std::test]
+async fn oversized_heads_get_a_400() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let mut request = b"GET / HTTP/1.1\r\nx-large: ".to_vec();
+    request.extend(vec![b'a'; 10 * 1024]);
+
+    // Act - the header line never ends
+    let response = raw_response(&mock_server, &request).await;
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert_eq!(status.as_u16(), 404);
+}
+
+#[async_std::test]
+async fn invalid_content_lengths_get_a_400() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act
+    let response = raw_response(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: ten\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("connection: close\r\n"));
+}
+
+#[async_std::test]
+async fn oversized_content_lengths_get_a_400() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act
+    let response = raw_response(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 18446744073709551615\r\n\r\n",
+    )
+    .await;
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert_eq!(status.as_u16(), 404);
+}
+
+#[async_std::test]
+async fn transfer_codings_not_ending_with_chunked_get_a_400() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act - the length of the body cannot be determined
+    let response = raw_response(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked, gzip\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+}
+
+#[async_std::test]
+async fn oversized_chunks_get_a_400() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act - the second chunk would overflow the length of the body read so far
+    let response = raw_response(
+        &mock_server,
+        b"POST / HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n\
+          1\r\na\r\nFFFFFFFFFFFFFFFF\r\n",
+    )
+    .await;
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert_eq!(status.as_u16(), 404);
+}
+
+#[async_std::test]
+async fn responses_can_be_sent_before_the_body_is_read() {
+    use async_std::prelude::*;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::builder()
+        .respond_before_reading_body()
+        .start()
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let mut stream = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+
+    // Act - we only send the head of a large upload
+    stream
+        .write_all(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 1000000\r\n\r\n")
+        .await
+        .unwrap();
+    let mut buffer = [0u8; 1024];
+    let bytes_read = async_std::future::timeout(Duration::from_secs(5), stream.read(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Assert
+    let response = String::from_utf8_lossy(&buffer[..bytes_read]).to_string();
+    assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+    assert!(response.contains("connection: close\r\n"));
+}
+
+#[async_std::test]
+async fn responding_before_the_body_does_not_block_a_streaming_client() {
+    use async_std::prelude::*;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::builder()
+        .respond_before_reading_body()
+        .start()
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(413))
+        .mount(&mock_server)
+        .await;
+    let body_length = 16 * 1024 * 1024;
+    let stream = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+    let mut upload = stream.clone();
+    let mut download = stream;
+
+    // Act - the client writes the whole body before reading anything
+    let client = async_std::task::spawn(async move {
+        let head = format!(
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+            body_length
+        );
+        upload.write_all(head.as_bytes()).await.unwrap();
+        upload.write_all(&vec![b'a'; body_length]).await.unwrap();
+        let mut response = Vec::new();
+        download.read_to_end(&mut response).await.unwrap();
+        String::from_utf8(response).unwrap()
+    });
+    let response = async_std::future::timeout(Duration::from_secs(10), client)
+        .await
+        .unwrap();
+
+    // Assert - the server consumed the body and closed the connection
+    assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+    assert_eq!(status.as_u16(), 404);
+}
+
+static SHARED_MOCK_SERVER: wiremock::LazyMockServer = wiremock::LazyMockServer::new();
+
+#[async_std::test]
+async fn lazy_mock_server_hands_out_the_same_server() {
+    // Act
+    let (first, second) = futures::join!(SHARED_MOCK_SERVER.get(), SHARED_MOCK_SERVER.get());
+    let third = SHARED_MOCK_SERVER.get().await;
+
+    // Assert
+    assert_eq!(first.address(), second.address());
+    assert_eq!(first.address(), third.address());
+}
+
+#[async_std::test]
+async fn lazy_mock_server_outlives_test_scopes() {
+    use wiremock::matchers::path;
+
+    // Arrange
+    let scope = wiremock::TestScope::new();
+    let mock_server = SHARED_MOCK_SERVER.get().await;
+    drop(scope);
+
+    // Act
+    let guard = mock_server
+        .register_scoped(
+            Mock::given(path("/outlives-test-scopes"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1),
+        )
+        .await;
+    let status = surf::get(mock_server.uri_for("/outlives-test-scopes"))
+        .await
+        .unwrap()
+        .status();
+    drop(guard);
+    let status_after_guard = surf::get(mock_server.uri_for("/outlives-test-scopes"))
+        .await
+        .unwrap()
+        .status();
+
+    // Assert
+    assert_eq!(status.as_u16(), 200);
+    assert_eq!(status_after_guard.as_u16(), 404);
+}
+
+#[async_std::test]
+#[should_panic(expected = "Verification failed")]
+async fn shared_mock_server_is_verified_when_the_last_handle_is_dropped() {
+    // Arrange
+    let mock_server = wiremock::SharedMockServer::start().await;
+    let handle = mock_server.clone();
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&handle)
+        .await;
+
+    // Act
+    drop(mock_server);
+    assert!(handle.is_running());
+    drop(handle);
+}
+
+#[async_std::test]
+async fn verify_no_unmatched_passes_if_every_request_matched() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Act - it does not panic
+    mock_server.verify_no_unmatched().await;
+}
+
+#[async_std::test]
+#[should_panic(expected = "GET http")]
+async fn verify_no_unmatched_lists_the_unmatched_requests() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    surf::post(mock_server.uri()).await.unwrap();
+    surf::get(mock_server.uri_for("/unexpected")).await.unwrap();
+
+    // Act
+    mock_server.verify_no_unmatched().await;
+}
+
+#[async_std::test]
+#[should_panic(expected = "1 request(s) did not match any mock. Request recording is disabled")]
+async fn verify_no_unmatched_counts_unmatched_requests_if_recording_is_disabled() {
+    // Arrange
+    let mock_server = MockServer::builder()
+        .disable_request_recording()
+        .start()
+        .await;
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Act
+    mock_server.verify_no_unmatched().await;
+}
+
+#[async_std::test]
+async fn verify_no_unmatched_forgets_requests_after_a_reset() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Act
+    mock_server.reset_counters().await;
+
+    // Assert - it does not panic
+    mock_server.verify_no_unmatched().await;
+}
+
+#[async_std::test]
+async fn unmatched_requests_get_the_configured_not_found_body() {
+    // Arrange
+    let mock_server = MockServer::builder()
+        .not_found_body("No mock matched.")
+        .start()
+        .await;
+    Mock::given(PathExactMatcher::new("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut unmatched = surf::get(mock_server.uri()).await.unwrap();
+    let mut matched = surf::get(mock_server.uri_for("/hello")).await.unwrap();
+
+    // Assert
+    assert_eq!(unmatched.status().as_u16(), 404);
+    assert_eq!(unmatched.body_string().await.unwrap(), "No mock matched.");
+    assert_eq!(matched.status().as_u16(), 200);
+    assert_eq!(matched.body_string().await.unwrap(), "");
+}
+
+#[async_std::test]
+async fn report_lists_mocks_expectations_and_unmatched_requests() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(PathExactMatcher::new("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .named("Hello")
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(PathExactMatcher::new("/never"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1..)
+        .mount(&mock_server)
+        .await;
+    surf::get(mock_server.uri_for("/hello")).await.unwrap();
+    surf::get(mock_server.uri_for("/missing")).await.unwrap();
+
+    // Act
+    let report = mock_server.report().await;
+
+    // Assert
+    assert!(report.contains("Requests received: 2 (1 unmatched)"));
+    assert!(report.contains(
+        "1. Hello [PathExactMatcher(\"/hello\")]: matched 1 request(s), expected 1 (satisfied)"
+    ));
+    assert!(report.contains(
+        "2. <unnamed> [PathExactMatcher(\"/never\")]: matched 0 request(s), expected 1.. (NOT satisfied)"
+    ));
+    assert!(report.contains("Unmatched requests (last 1 of 1):"));
+    assert!(report.contains("/missing"));
+    // Do not fail the test on drop because of the unsatisfied expectation.
+    mock_server.reset().await;
+}
+
+#[async_std::test]
+async fn report_only_lists_the_last_unmatched_requests() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    for i in 0..12 {
+        surf::get(mock_server.uri_for(&format!("/missing-{}", i)))
+            .await
+            .unwrap();
+    }
+
+    // Act
+    let report = mock_server.report().await;
+
+    // Assert
+    assert!(report.contains("Unmatched requests (last 10 of 12):"));
+    assert!(!report.contains("/missing-0\n"));
+    assert!(!report.contains("/missing-1\n"));
+    assert!(report.contains("/missing-2\n"));
+    assert!(report.contains("/missing-11\n"));
+}
+
+#[async_std::test]
+#[should_panic(expected = "200 is not a redirection status code.")]
+async fn redirect_chains_reject_non_redirection_status_codes() {
+    let mock_server = MockServer::start().await;
+    mock_server
+        .mount_redirect_chain_with_status(&["/0", "/1"], 200, ResponseTemplate::new(200))
+        .await;
+}
+
+#[async_std::test]
+async fn disabled_mocks_are_skipped_until_they_are_enabled_again() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let guard = mock_server
+        .register_scoped(
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(2),
+        )
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let before = surf::get(mock_server.uri()).await.unwrap().status();
+    guard.disable();
+    let during = surf::get(mock_server.uri()).await.unwrap().status();
+    let disabled = guard.is_enabled();
+    guard.enable();
+    let after = surf::get(mock_server.uri()).await.unwrap().status();
+
+    // Assert - the request received while disabled fell through to the next mock
+    assert_eq!(before.as_u16(), 200);
+    assert_eq!(during.as_u16(), 500);
+    assert!(!disabled);
+    assert_eq!(after.as_u16(), 200);
+    assert_eq!(guard.received_requests().await.len(), 2);
+}
+
+#[async_std::test]
+#[should_panic(expected = "Verification failed: scoped mock expectations have not been satisfied.")]
+async fn disabled_mocks_are_still_verified_on_drop() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let guard = mock_server
+        .register_scoped(
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1),
+        )
+        .await;
+    guard.disable();
+
+    // Act
+    surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert - panics when `guard` is dropped
+}
+
+#[async_std::test]
+async fn request_stream_yields_requests_as_they_arrive() {
+    use futures::StreamExt;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(PathExactMatcher::new("/matched"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    surf::get(mock_server.uri_for("/before")).await.unwrap();
+    let mut first = mock_server.request_stream().await;
+    let mut second = mock_server.request_stream().await;
+
+    // Act
+    let matched = surf::get(mock_server.uri_for("/matched"))
+        .await
+        .unwrap()
+        .status();
+    surf::get(mock_server.uri_for("/unmatched")).await.unwrap();
+
+    // Assert - requests are still served, each stream gets all of them from subscription on
+    assert_eq!(matched.as_u16(), 200);
+    for stream in [&mut first, &mut second].iter_mut() {
+        assert_eq!(stream.next().await.unwrap().url.path(), "/matched");
+        assert_eq!(stream.next().await.unwrap().url.path(), "/unmatched");
+    }
+}
+
+#[async_std::test]
+async fn request_stream_survives_consumers_hanging_up_and_ends_with_the_server() {
+    use async_std::future::timeout;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let dropped = mock_server.request_stream().await;
+    let mut kept = mock_server.request_stream().await;
+    drop(dropped);
+
+    // Act
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+    drop(mock_server);
+
+    // Assert
+    assert_eq!(status.as_u16(), 404);
+    assert!(kept.next().await.is_some());
+    let end = timeout(Duration::from_secs(5), kept.next()).await;
+    assert!(end.unwrap().is_none());
+}
+
+#[async_std::test]
+async fn http_1_0_servers_close_the_connection_after_each_response() {
+    // Arrange
+    let mock_server = MockServer::builder().http_1_0().start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+        .mount(&mock_server)
+        .await;
+
+    // Act - `raw_response` reads up to EOF: it only returns if the connection is closed.
+    let legacy = raw_response(&mock_server, b"GET / HTTP/1.0\r\n\r\n").await;
+    let modern = raw_response(&mock_server, b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+
+    // Assert
+    for response in &[legacy, modern] {
+        assert!(response.starts_with("HTTP/1.0 200 OK\r\n"));
+        assert!(response.contains("connection: close\r\n"));
+        assert!(response.contains("content-length: 5\r\n"));
+        assert!(response.ends_with("\r\n\r\nhello"));
+    }
+    assert_eq!(mock_server.total_requests().await, 2);
+}
+
+#[async_std::test]
+async fn http_1_0_requests_are_rejected_by_default() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+
+    // Act
+    let response = raw_response(&mock_server, b"GET / HTTP/1.0\r\n\r\n").await;
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+}
+
+#[async_std::test]
+async fn response_middlewares_run_in_order_on_every_response() {
+    // Arrange
+    let mock_server = MockServer::builder()
+        .response_middleware(|request, mut response| {
+            response
+                .insert_header("x-path", request.url.path())
+                .unwrap();
+            response.insert_header("x-layer", "first").unwrap();
+            response
+        })
+        .response_middleware(|_, mut response| {
+            // It sees what the first middleware did
+            let name = "x-layer".parse().unwrap();
+            let layers = format!("{}, second", response.header(&name).unwrap()[0]);
+            response.insert_header("x-layer", layers.as_str()).unwrap();
+            response
+        })
+        .start()
+        .await;
+    Mock::given(PathExactMatcher::new("/hello"))
+        .respond_with(ResponseTemplate::new(200).insert_header("x-mock", "hello"))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let matched = surf::get(mock_server.uri_for("/hello")).await.unwrap();
+    let unmatched = surf::get(mock_server.uri_for("/missing")).await.unwrap();
+
+    // Assert - the template's headers are preserved
+    assert_eq!(matched.status().as_u16(), 200);
+    assert_eq!(matched.header("x-mock"), Some("hello"));
+    assert_eq!(matched.header("x-path"), Some("/hello"));
+    assert_eq!(matched.header("x-layer"), Some("first, second"));
+    assert_eq!(unmatched.status().as_u16(), 404);
+    assert_eq!(unmatched.header("x-path"), Some("/missing"));
+}
+
+#[async_std::test]
+async fn request_middlewares_normalise_requests_before_recording_and_matching() {
+    // Arrange - timestamps are truncated to the day
+    let mock_server = MockServer::builder()
+        .request_middleware(|mut request| {
+            let name = "x-timestamp".parse().unwrap();
+            if let Some(values) = request.headers.get_mut(&name) {
+                let day = values[0].as_str()[..10].to_owned();
+                *values = vec![day.parse().unwrap()];
+            }
+            request
+        })
+        .start()
+        .await;
+    Mock::given(wiremock::matchers::header("x-timestamp", "2020-06-01"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = surf::get(mock_server.uri())
+        .set_header("x-timestamp", "2020-06-01T12:34:56Z")
+        .await
+        .unwrap()
+        .status();
+
+    // Assert
+    assert_eq!(status.as_u16(), 200);
+    let recorded = mock_server.received_requests().await.unwrap();
+    let name = "x-timestamp".parse().unwrap();
+    assert_eq!(recorded[0].headers[&name][0].as_str(), "2020-06-01");
+}
+
+#[async_std::test]
+async fn recorded_requests_know_which_mock_matched_them() {
+    // Arrange - overlapping mocks: the first one mounted wins
+    let mock_server = MockServer::start().await;
+    Mock::given(PathExactMatcher::new("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .named("Hello")
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    let guard = mock_server
+        .register_scoped(Mock::given(method("GET")).respond_with(ResponseTemplate::new(200)))
+        .await;
+
+    // Act
+    surf::get(mock_server.uri_for("/hello")).await.unwrap();
+    surf::get(mock_server.uri_for("/hello")).await.unwrap();
+    surf::post(mock_server.uri_for("/hello")).await.unwrap();
+
+    // Assert
+    let recorded = mock_server.received_requests().await.unwrap();
+    assert_eq!(recorded[0].matched_mock.as_deref(), Some("Hello"));
+    assert_eq!(recorded[1].matched_mock.as_deref(), Some("unnamed mock #1"));
+    assert_eq!(recorded[2].matched_mock, None);
+    let scoped = guard.received_requests().await;
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].matched_mock.as_deref(), Some("unnamed mock #1"));
+}
+
+#[async_std::test]
+async fn connections_are_closed_after_the_maximum_number_of_requests() {
+    use async_std::future::timeout;
+    use async_std::prelude::*;
+    use std::time::Duration;
+
+    // Arrange
+    let mock_server = MockServer::builder()
+        .max_requests_per_connection(2)
+        .start()
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    let request = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let mut stream = async_std::net::TcpStream::connect(mock_server.address())
+        .await
+        .unwrap();
+    let mut buffer = [0u8; 1024];
+
+    // Act
+    stream.write_all(request).await.unwrap();
+    let bytes_read = stream.read(&mut buffer).await.unwrap();
+    let first = String::from_utf8_lossy(&buffer[..bytes_read]).into_owned();
+    stream.write_all(request).await.unwrap();
+    let bytes_read = stream.read(&mut buffer).await.unwrap();
+    let second = String::from_utf8_lossy(&buffer[..bytes_read]).into_owned();
+    let eof = timeout(Duration::from_secs(5), stream.read(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Assert - the client has to open a new connection
+    assert!(first.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(!first.contains("connection: close"));
+    assert!(second.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(second.contains("connection: close\r\n"));
+    assert_eq!(eof, 0);
+    let status = surf::get(mock_server.uri()).await.unwrap().status();
+    assert_eq!(status.as_u16(), 200);
+}
+
+#[test]
+#[should_panic(expected = "The maximum number of requests per connection must be greater than 0.")]
+fn a_limit_of_zero_requests_per_connection_is_rejected() {
+    MockServer::builder().max_requests_per_connection(0);
+}
+
+#[async_std::test]
+async fn response_builds_the_response_of_the_template() {
+    // Arrange
+    let mock = Mock::given(method("GET")).respond_with(ResponseTemplate::new(201));
+
+    // Act
+    let response = mock.response();
+
+    // Assert
+    assert_eq!(u16::from(response.status()), 201);
+}
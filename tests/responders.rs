@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use wiremock::matchers::method;
+use wiremock::responders::{by_header, recovering, weighted};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn statuses(mock_server: &MockServer, n_requests: usize) -> Vec<u16> {
+    let mut statuses = vec![];
+    for _ in 0..n_requests {
+        let status = surf::get(mock_server.uri()).await.unwrap().status();
+        statuses.push(status.as_u16());
+    }
+    statuses
+}
+
+#[async_std::test]
+async fn weighted_responder_never_picks_a_template_with_zero_weight() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let responder = weighted(vec![
+        (ResponseTemplate::new(200), 1),
+        (ResponseTemplate::new(500), 0),
+    ]);
+    Mock::given(method("GET"))
+        .respond_with(responder)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let statuses = statuses(&mock_server, 10).await;
+
+    // Assert
+    assert!(statuses.iter().all(|s| *s == 200));
+}
+
+#[async_std::test]
+async fn weighted_responder_with_the_same_seed_is_reproducible() {
+    // Arrange
+    let responder = || {
+        weighted(vec![
+            (ResponseTemplate::new(200), 1),
+            (ResponseTemplate::new(500), 1),
+        ])
+        .seed(1234)
+    };
+    let first_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(responder())
+        .mount(&first_server)
+        .await;
+    let second_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(responder())
+        .mount(&second_server)
+        .await;
+
+    // Act
+    let first_statuses = statuses(&first_server, 10).await;
+    let second_statuses = statuses(&second_server, 10).await;
+
+    // Assert
+    assert_eq!(first_statuses, second_statuses);
+    assert!(first_statuses.contains(&200));
+    assert!(first_statuses.contains(&500));
+}
+
+#[async_std::test]
+async fn mock_servers_with_the_same_rng_seed_are_reproducible() {
+    // Arrange
+    let responder = || {
+        weighted(vec![
+            (ResponseTemplate::new(200), 1),
+            (ResponseTemplate::new(500), 1),
+        ])
+    };
+    let first_server = MockServer::builder().rng_seed(42).start().await;
+    Mock::given(method("GET"))
+        .respond_with(responder())
+        .mount(&first_server)
+        .await;
+    let second_server = MockServer::builder().rng_seed(42).start().await;
+    Mock::given(method("GET"))
+        .respond_with(responder())
+        .mount(&second_server)
+        .await;
+
+    // Act
+    let first_statuses = statuses(&first_server, 10).await;
+    let second_statuses = statuses(&second_server, 10).await;
+
+    // Assert
+    assert_eq!(first_statuses, second_statuses);
+    assert!(first_statuses.contains(&200));
+    assert!(first_statuses.contains(&500));
+}
+
+#[async_std::test]
+async fn by_header_responder_picks_the_template_for_the_header_value() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let mut templates = HashMap::new();
+    templates.insert("application/json", ResponseTemplate::new(200));
+    templates.insert("application/xml", ResponseTemplate::new(201));
+    Mock::given(method("GET"))
+        .respond_with(by_header("Accept", templates, ResponseTemplate::new(406)))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let status = |accept: &'static str| {
+        let uri = mock_server.uri();
+        async move {
+            surf::get(uri)
+                .set_header("Accept", accept)
+                .await
+                .unwrap()
+                .status()
+                .as_u16()
+        }
+    };
+    let json = status("application/json").await;
+    let xml = status("application/xml").await;
+    let csv = status("text/csv").await;
+
+    // Assert
+    assert_eq!(json, 200);
+    assert_eq!(xml, 201);
+    assert_eq!(csv, 406);
+}
+
+#[async_std::test]
+async fn by_header_responder_falls_back_to_default_without_the_header() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let templates = vec![("application/json", ResponseTemplate::new(200))];
+    Mock::given(method("GET"))
+        .respond_with(by_header("Accept", templates, ResponseTemplate::new(406)))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let statuses = statuses(&mock_server, 1).await;
+
+    // Assert
+    assert_eq!(statuses, vec![406]);
+}
+
+#[async_std::test]
+async fn recovering_responder_fails_first_and_recovers_afterwards() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    // The first request always fails, the following ones never do.
+    Mock::given(method("GET"))
+        .respond_with(recovering(1.0, 0.0))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let statuses = statuses(&mock_server, 4).await;
+
+    // Assert
+    assert_eq!(statuses, vec![500, 200, 200, 200]);
+}
+
+#[async_std::test]
+async fn recovering_responder_with_the_same_seed_is_reproducible() {
+    // Arrange
+    let responder = || recovering(0.9, 0.8).seed(7);
+    let first_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(responder())
+        .mount(&first_server)
+        .await;
+    let second_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(responder())
+        .mount(&second_server)
+        .await;
+
+    // Act
+    let first_statuses = statuses(&first_server, 20).await;
+    let second_statuses = statuses(&second_server, 20).await;
+
+    // Assert
+    assert_eq!(first_statuses, second_statuses);
+}
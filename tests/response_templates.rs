@@ -0,0 +1,702 @@
+use async_std::net::TcpStream;
+use async_std::prelude::*;
+use wiremock::matchers::{is_cors_preflight, method};
+use wiremock::{Error, Mock, MockServer, ResponseTemplate};
+
+async fn ranged_server() -> MockServer {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::ranged_body("hello world"))
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
+
+async fn get_range(mock_server: &MockServer, range: Option<&str>) -> surf::Response {
+    let mut request = surf::get(mock_server.uri());
+    if let Some(range) = range {
+        request = request.set_header("Range", range);
+    }
+    request.await.unwrap()
+}
+
+#[async_std::test]
+async fn ranged_body_returns_the_whole_body_without_a_range_header() {
+    // Arrange
+    let mock_server = ranged_server().await;
+
+    // Act
+    let mut response = get_range(&mock_server, None).await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.header("Accept-Ranges"), Some("bytes"));
+    assert_eq!(response.header("Content-Range"), None);
+    assert_eq!(response.body_string().await.unwrap(), "hello world");
+}
+
+#[async_std::test]
+async fn ranged_body_returns_partial_content_for_satisfiable_ranges() {
+    // Arrange
+    let mock_server = ranged_server().await;
+    let cases = vec![
+        ("bytes=0-4", "bytes 0-4/11", "hello"),
+        ("bytes=6-", "bytes 6-10/11", "world"),
+        ("bytes=-3", "bytes 8-10/11", "rld"),
+        // The last byte position is capped to the length of the body
+        ("bytes=6-100", "bytes 6-10/11", "world"),
+    ];
+
+    for (range, expected_content_range, expected_body) in cases {
+        // Act
+        let mut response = get_range(&mock_server, Some(range)).await;
+
+        // Assert
+        assert_eq!(response.status().as_u16(), 206);
+        assert_eq!(
+            response.header("Content-Range"),
+            Some(expected_content_range)
+        );
+        assert_eq!(response.body_string().await.unwrap(), expected_body);
+    }
+}
+
+#[async_std::test]
+async fn ranged_body_returns_416_for_unsatisfiable_ranges() {
+    // Arrange
+    let mock_server = ranged_server().await;
+
+    // Act
+    let response = get_range(&mock_server, Some("bytes=11-")).await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 416);
+    assert_eq!(response.header("Content-Range"), Some("bytes */11"));
+}
+
+#[async_std::test]
+async fn ranged_body_returns_the_whole_body_for_multiple_ranges() {
+    // Arrange
+    let mock_server = ranged_server().await;
+
+    // Act
+    let mut response = get_range(&mock_server, Some("bytes=0-1,3-4")).await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.body_string().await.unwrap(), "hello world");
+}
+
+struct Payload(Option<Vec<u8>>);
+
+impl std::convert::TryFrom<Payload> for Vec<u8> {
+    type Error = &'static str;
+
+    fn try_from(payload: Payload) -> Result<Self, Self::Error> {
+        payload.0.ok_or("empty payload")
+    }
+}
+
+struct InvalidString;
+
+impl std::convert::TryFrom<InvalidString> for String {
+    type Error = &'static str;
+
+    fn try_from(_: InvalidString) -> Result<Self, Self::Error> {
+        Err("not a string")
+    }
+}
+
+#[test]
+fn try_variants_return_an_error_on_invalid_inputs() {
+    let template = ResponseTemplate::new(200).try_insert_header("hé", "value");
+    assert!(matches!(template, Err(Error::InvalidHeaderName(_))));
+
+    let template = ResponseTemplate::new(200).try_insert_header("x-header", "välue");
+    assert!(matches!(template, Err(Error::InvalidHeaderValue(_))));
+
+    let template = ResponseTemplate::new(200).try_append_header("hé", "value");
+    assert!(matches!(template, Err(Error::InvalidHeaderName(_))));
+
+    let template = ResponseTemplate::new(200).try_set_body_bytes(Payload(None));
+    assert!(matches!(template, Err(Error::InvalidBody(_))));
+
+    let template = ResponseTemplate::new(200).try_set_body_raw(Payload(None), "text/plain");
+    assert!(matches!(template, Err(Error::InvalidBody(_))));
+
+    let template = ResponseTemplate::new(200).try_set_body_raw("body", "text/plaın");
+    assert!(matches!(template, Err(Error::InvalidHeaderValue(_))));
+
+    let template = ResponseTemplate::new(200).try_set_body_string(InvalidString);
+    assert!(matches!(template, Err(Error::InvalidBody(_))));
+
+    let template = ResponseTemplate::new(200).try_append_trailer("hé", "value");
+    assert!(matches!(template, Err(Error::InvalidHeaderName(_))));
+
+    let template = ResponseTemplate::try_new(1000);
+    assert!(matches!(template, Err(Error::InvalidStatusCode(_))));
+
+    // JSON object keys must be strings
+    let mut not_json = std::collections::HashMap::new();
+    not_json.insert(vec![1u8], "value");
+    let template = ResponseTemplate::new(200).try_set_body_json(not_json);
+    assert!(matches!(template, Err(Error::BodySerialization(_))));
+}
+
+#[async_std::test]
+async fn try_variants_build_the_same_template_on_valid_inputs() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200)
+        .try_insert_header("x-inserted", "a")
+        .and_then(|t| t.try_append_header("x-appended", "b"))
+        .and_then(|t| t.try_set_body_bytes(Payload(Some(b"body".to_vec()))))
+        .unwrap();
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut response = surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert
+    assert_eq!(response.header("x-inserted"), Some("a"));
+    assert_eq!(response.header("x-appended"), Some("b"));
+    assert_eq!(response.body_string().await.unwrap(), "body");
+}
+
+#[test]
+#[should_panic(expected = "Failed to convert into header name")]
+fn panicking_variants_panic_on_invalid_inputs() {
+    ResponseTemplate::new(200).insert_header("hé", "value");
+}
+
+#[test]
+#[should_panic(expected = "Failed to convert into status code")]
+fn new_panics_on_invalid_status_codes() {
+    ResponseTemplate::new(1000);
+}
+
+/// Write `request` on a raw TCP connection to `mock_server` and read back the response
+/// until its bytes end with `terminator`.
+async fn raw_exchange(mock_server: &MockServer, request: &[u8], terminator: &[u8]) -> String {
+    let mut stream = TcpStream::connect(mock_server.address()).await.unwrap();
+    stream.write_all(request).await.unwrap();
+
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !response.ends_with(terminator) {
+        let bytes_read = stream.read(&mut buffer).await.unwrap();
+        assert_ne!(
+            bytes_read, 0,
+            "Connection closed before the end of the response"
+        );
+        response.extend_from_slice(&buffer[..bytes_read]);
+    }
+    String::from_utf8(response).unwrap()
+}
+
+#[async_std::test]
+async fn trailers_are_sent_after_a_chunked_body() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200)
+        .set_body_bytes("hello world")
+        .append_trailer("grpc-status", "0");
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response = raw_exchange(
+        &mock_server,
+        b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        b"0\r\ngrpc-status: 0\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    let (head, body) = response.split_at(response.find("\r\n\r\n").unwrap() + 4);
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(head.contains("transfer-encoding: chunked\r\n"));
+    assert!(head.contains("trailer: grpc-status\r\n"));
+    assert!(!head.contains("content-length"));
+    assert_eq!(body, "B\r\nhello world\r\n0\r\ngrpc-status: 0\r\n\r\n");
+}
+
+#[async_std::test]
+async fn responses_without_trailers_have_a_content_length() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes("hello world"))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response = raw_exchange(
+        &mock_server,
+        b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        b"hello world",
+    )
+    .await;
+
+    // Assert
+    assert!(response.contains("content-length: 11\r\n"));
+    assert!(!response.contains("transfer-encoding"));
+}
+
+#[async_std::test]
+async fn cors_preflight_emits_all_the_access_control_headers() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::cors_preflight(
+        "https://example.com",
+        vec!["GET", "POST", "DELETE"],
+        vec!["Content-Type", "Authorization"],
+    );
+    Mock::given(is_cors_preflight())
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response = raw_exchange(
+        &mock_server,
+        b"OPTIONS /resource HTTP/1.1\r\n\
+          Host: localhost\r\n\
+          Origin: https://example.com\r\n\
+          Access-Control-Request-Method: DELETE\r\n\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert!(response.starts_with("HTTP/1.1 204 No Content\r\n"));
+    assert!(response.contains("access-control-allow-origin: https://example.com\r\n"));
+    assert!(response.contains("access-control-allow-methods: GET, POST, DELETE\r\n"));
+    assert!(response.contains("access-control-allow-headers: Content-Type, Authorization\r\n"));
+}
+
+#[async_std::test]
+async fn cors_preflight_omits_allowed_headers_if_empty() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::cors_preflight("*", vec!["GET"], Vec::<String>::new());
+    Mock::given(is_cors_preflight())
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response = raw_exchange(
+        &mock_server,
+        b"OPTIONS / HTTP/1.1\r\nHost: localhost\r\nAccess-Control-Request-Method: GET\r\n\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    assert!(response.contains("access-control-allow-origin: *\r\n"));
+    assert!(!response.contains("access-control-allow-headers"));
+}
+
+/// Write `request` on a raw TCP connection to `mock_server` and read back everything
+/// until the server closes the connection.
+async fn raw_exchange_to_end(mock_server: &MockServer, request: &[u8]) -> String {
+    let mut stream = TcpStream::connect(mock_server.address()).await.unwrap();
+    stream.write_all(request).await.unwrap();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+    String::from_utf8(response).unwrap()
+}
+
+#[async_std::test]
+async fn corrupt_content_length_can_be_larger_than_the_body() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_corrupt_content_length(20, "short"))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response =
+        raw_exchange_to_end(&mock_server, b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+
+    // Assert - the connection is closed right after the (truncated) body
+    assert!(response.contains("content-length: 20\r\n"));
+    assert!(response.ends_with("\r\n\r\nshort"));
+}
+
+#[async_std::test]
+async fn corrupt_content_length_can_be_smaller_than_the_body() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200)
+        .set_corrupt_content_length(2, "hello")
+        .append_trailer("grpc-status", "0");
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response =
+        raw_exchange_to_end(&mock_server, b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+
+    // Assert - the whole body is sent anyway and trailers are ignored
+    assert!(response.contains("content-length: 2\r\n"));
+    assert!(!response.contains("transfer-encoding"));
+    assert!(response.ends_with("\r\n\r\nhello"));
+}
+
+#[async_std::test]
+async fn body_generator_is_invoked_for_every_response() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let n_calls = AtomicUsize::new(0);
+    let template = ResponseTemplate::new(200).set_body_generator(move || {
+        let n = n_calls.fetch_add(1, Ordering::SeqCst);
+        format!("call #{}", n).into_bytes()
+    });
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut bodies = vec![];
+    for _ in 0..3 {
+        let mut response = surf::get(mock_server.uri()).await.unwrap();
+        bodies.push(response.body_string().await.unwrap());
+    }
+
+    // Assert
+    assert_eq!(bodies, vec!["call #0", "call #1", "call #2"]);
+}
+
+#[async_std::test]
+async fn static_bodies_replace_a_body_generator() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200)
+        .set_body_generator(|| b"generated".to_vec())
+        .set_body_string("static");
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut response = surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert
+    assert_eq!(response.body_string().await.unwrap(), "static");
+}
+
+#[async_std::test]
+async fn problem_json_follows_the_rfc_7807_shape() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::problem_json(
+            404,
+            "Not found",
+            "There is no user with id 42.",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut response = surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(
+        response.header("Content-Type"),
+        Some("application/problem+json")
+    );
+    let body: serde_json::Value = response.body_json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "Not found",
+            "status": 404,
+            "detail": "There is no user with id 42."
+        })
+    );
+}
+
+#[async_std::test]
+async fn head_requests_get_the_content_length_but_no_body() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes("hello world"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes("hello world"))
+        .mount(&mock_server)
+        .await;
+
+    // Act - if the body of the HEAD response were sent, it would be read as the start
+    // of the response to the GET request
+    let response = raw_exchange(
+        &mock_server,
+        b"HEAD / HTTP/1.1\r\nHost: localhost\r\n\r\nGET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        b"hello world",
+    )
+    .await;
+
+    // Assert
+    let responses: Vec<&str> = response.split("HTTP/1.1 200 OK\r\n").skip(1).collect();
+    assert_eq!(responses.len(), 2);
+    assert!(responses[0].contains("content-length: 11\r\n"));
+    assert!(responses[0].ends_with("\r\n\r\n"));
+    assert!(responses[1].contains("content-length: 11\r\n"));
+    assert!(responses[1].ends_with("\r\n\r\nhello world"));
+}
+
+#[async_std::test]
+async fn headers_are_sent_in_insertion_order() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200)
+        .insert_header("x-zulu", "1")
+        .append_header("x-alpha", "2")
+        .insert_header("x-mike", "3")
+        .append_header("x-zulu", "4")
+        // Replacing a header keeps its position
+        .insert_header("x-alpha", "5");
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response = raw_exchange(
+        &mock_server,
+        b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+
+    // Assert
+    let custom_headers: Vec<&str> = response
+        .lines()
+        .filter(|line| line.starts_with("x-"))
+        .collect();
+    assert_eq!(
+        custom_headers,
+        vec!["x-zulu: 1", "x-zulu: 4", "x-alpha: 5", "x-mike: 3"]
+    );
+}
+
+#[async_std::test]
+async fn throttled_bodies_are_sent_in_full_at_the_configured_rate() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let body = vec![b'a'; 2_000];
+    let template = ResponseTemplate::new(200)
+        .set_body_bytes(body.clone())
+        .set_throughput(5_000);
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let start = std::time::Instant::now();
+    let received = surf::get(mock_server.uri())
+        .await
+        .unwrap()
+        .body_bytes()
+        .await
+        .unwrap();
+
+    // Assert - 2000 bytes at 5000 bytes per second take at least 400 milliseconds
+    assert_eq!(received, body);
+    assert!(start.elapsed() >= std::time::Duration::from_millis(350));
+}
+
+#[test]
+#[should_panic]
+fn a_throughput_of_zero_is_rejected() {
+    let _ = ResponseTemplate::new(200).set_throughput(0);
+}
+
+#[cfg(feature = "brotli")]
+mod brotli {
+    use async_std::net::TcpStream;
+    use async_std::prelude::*;
+    use std::io::Read;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[async_std::test]
+    async fn brotli_bodies_round_trip() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+        let body = "hello world! ".repeat(100);
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_brotli(body.as_str()))
+            .mount(&mock_server)
+            .await;
+
+        // Act
+        let mut stream = TcpStream::connect(mock_server.address()).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        let mut buffer = [0u8; 1024];
+        let (head, content_length) = loop {
+            let bytes_read = stream.read(&mut buffer).await.unwrap();
+            assert_ne!(
+                bytes_read, 0,
+                "Connection closed before the end of the head"
+            );
+            response.extend_from_slice(&buffer[..bytes_read]);
+            if let Some(end) = response.windows(4).position(|w| w == b"\r\n\r\n") {
+                let head = String::from_utf8(response.drain(..end + 4).collect()).unwrap();
+                let content_length: usize = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .unwrap()
+                    .parse()
+                    .unwrap();
+                break (head, content_length);
+            }
+        };
+        let start = response.len();
+        response.resize(content_length, 0);
+        stream.read_exact(&mut response[start..]).await.unwrap();
+
+        // Assert
+        assert!(head.contains("content-encoding: br\r\n"));
+        let compressed = &response[..];
+        assert!(compressed.len() < body.len());
+        let mut decompressed = String::new();
+        brotli_decompressor::Decompressor::new(compressed, 4096)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+    }
+}
+
+#[async_std::test]
+async fn from_response_replays_status_headers_and_body() {
+    use http_types::{Response, StatusCode};
+
+    // Arrange
+    let mut captured = Response::new(StatusCode::Accepted);
+    captured
+        .insert_header("content-type", "application/json")
+        .unwrap();
+    captured.insert_header("x-request-id", "42").unwrap();
+    captured.append_header("set-cookie", "a=1").unwrap();
+    captured.append_header("set-cookie", "b=2").unwrap();
+    // Framing headers are recomputed by the server
+    captured.insert_header("content-length", "1000").unwrap();
+    captured.set_body(r#"{"id":1}"#);
+    let template = ResponseTemplate::from_response(captured).await;
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let response = raw_exchange(
+        &mock_server,
+        b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        br#"{"id":1}"#,
+    )
+    .await;
+
+    // Assert
+    let (head, body) = response.split_at(response.find("\r\n\r\n").unwrap() + 4);
+    assert!(head.starts_with("HTTP/1.1 202 Accepted\r\n"));
+    assert!(head.contains("content-type: application/json\r\n"));
+    assert!(head.contains("x-request-id: 42\r\n"));
+    assert!(head.contains("set-cookie: a=1\r\nset-cookie: b=2\r\n"));
+    assert_eq!(head.matches("content-length").count(), 1);
+    assert!(head.contains("content-length: 8\r\n"));
+    assert_eq!(body, r#"{"id":1}"#);
+}
+
+#[async_std::test]
+async fn from_response_keeps_empty_bodies_empty() {
+    use http_types::{Response, StatusCode};
+
+    // Arrange
+    let template = ResponseTemplate::from_response(Response::new(StatusCode::NoContent)).await;
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let mut response = surf::get(mock_server.uri()).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 204);
+    assert_eq!(response.body_string().await.unwrap(), "");
+}
+
+#[async_std::test]
+async fn jsonrpc_results_and_errors_are_conformant_responses() {
+    use serde_json::{json, Value};
+    use wiremock::matchers::jsonrpc_method;
+
+    // Arrange
+    let mock_server = MockServer::start().await;
+    Mock::given(jsonrpc_method("subtract"))
+        .respond_with(ResponseTemplate::jsonrpc_result(1, 19))
+        .mount(&mock_server)
+        .await;
+    Mock::given(jsonrpc_method("divide"))
+        .respond_with(ResponseTemplate::jsonrpc_error(
+            "two",
+            -32000,
+            "Division by zero",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    let call = |method: &str, id: Value| {
+        let body = json!({"jsonrpc": "2.0", "method": method, "id": id});
+        surf::post(mock_server.uri()).body_json(&body).unwrap()
+    };
+    let mut result = call("subtract", json!(1)).await.unwrap();
+    let mut error = call("divide", json!("two")).await.unwrap();
+
+    // Assert
+    assert_eq!(result.status().as_u16(), 200);
+    assert_eq!(result.header("content-type"), Some("application/json"));
+    let result: Value = result.body_json().await.unwrap();
+    assert_eq!(result, json!({"jsonrpc": "2.0", "result": 19, "id": 1}));
+
+    assert_eq!(error.status().as_u16(), 200);
+    let error: Value = error.body_json().await.unwrap();
+    assert_eq!(
+        error,
+        json!({
+            "jsonrpc": "2.0",
+            "error": {"code": -32000, "message": "Division by zero"},
+            "id": "two"
+        })
+    );
+}
@@ -1,3 +1,4 @@
+use reqwest::redirect::Policy;
 use reqwest::Client;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -38,3 +39,51 @@ async fn hello_surf() {
 
     assert_eq!(status.as_u16(), 200);
 }
+
+#[tokio::test]
+async fn reqwest_follows_a_redirect_chain() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    mock_server
+        .mount_redirect_chain(
+            &["/0", "/1", "/2", "/3"],
+            ResponseTemplate::new(200).set_body_string("the end"),
+        )
+        .await;
+
+    // Act
+    let resp = Client::new()
+        .get(&mock_server.uri_for("/0"))
+        .send()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.url().path(), "/3");
+    assert_eq!(resp.text().await.unwrap(), "the end");
+    assert_eq!(mock_server.total_requests().await, 4);
+}
+
+#[tokio::test]
+async fn reqwest_gives_up_on_a_redirect_chain_longer_than_its_limit() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    mock_server
+        .mount_redirect_chain_with_status(
+            &["/0", "/1", "/2", "/3"],
+            307,
+            ResponseTemplate::new(200),
+        )
+        .await;
+    let client = Client::builder()
+        .redirect(Policy::limited(2))
+        .build()
+        .unwrap();
+
+    // Act
+    let outcome = client.get(&mock_server.uri_for("/0")).send().await;
+
+    // Assert
+    assert!(outcome.unwrap_err().is_redirect());
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "tracing")]
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+type Fields = HashMap<String, String>;
+
+/// A bare-bones subscriber collecting the fields of every `wiremock_request` span, along
+/// with the names of the child spans explicitly opened within it.
+#[derive(Clone, Default)]
+struct RequestSpans {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<HashMap<u64, Fields>>>,
+    children: Arc<Mutex<HashMap<u64, Vec<String>>>>,
+}
+
+struct FieldsVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldsVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+}
+
+impl Subscriber for RequestSpans {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        if span.metadata().name() == "wiremock_request" {
+            let mut fields = Fields::new();
+            span.record(&mut FieldsVisitor(&mut fields));
+            self.spans.lock().unwrap().insert(id, fields);
+        }
+        if let Some(parent) = span.parent() {
+            self.children
+                .lock()
+                .unwrap()
+                .entry(parent.into_u64())
+                .or_default()
+                .push(span.metadata().name().to_owned());
+        }
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(fields) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(&mut FieldsVisitor(fields));
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[async_std::test]
+async fn each_request_gets_a_span_with_method_path_matched_mock_and_status() {
+    // Arrange
+    let subscriber = RequestSpans::default();
+    tracing::subscriber::set_global_default(subscriber.clone()).unwrap();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .named("Hello")
+        .mount(&mock_server)
+        .await;
+
+    // Act
+    surf::get(format!("{}/hello", mock_server.uri()))
+        .await
+        .unwrap();
+    surf::post(format!("{}/missing", mock_server.uri()))
+        .await
+        .unwrap();
+
+    // Assert
+    let children = subscriber.children.lock().unwrap().clone();
+    for (id, fields) in subscriber.spans.lock().unwrap().iter() {
+        // Each stage of handling the request happens within the request span - no response
+        // is generated from a template if no mock matched.
+        let expected = match fields["path"].as_str() {
+            "/hello" => vec![
+                "read_body",
+                "match_mocks",
+                "generate_response",
+                "write_response",
+            ],
+            _ => vec!["read_body", "match_mocks", "write_response"],
+        };
+        assert_eq!(children[id], expected);
+    }
+    let mut spans: Vec<Fields> = subscriber.spans.lock().unwrap().values().cloned().collect();
+    spans.sort_by_key(|fields| fields["path"].clone());
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0]["method"], "GET");
+    assert_eq!(spans[0]["path"], "/hello");
+    assert_eq!(spans[0]["matched_mock"], "Hello");
+    assert_eq!(spans[0]["status"], "200");
+    assert_eq!(spans[1]["method"], "POST");
+    assert_eq!(spans[1]["path"], "/missing");
+    assert!(!spans[1].contains_key("matched_mock"));
+    assert_eq!(spans[1]["status"], "404");
+}